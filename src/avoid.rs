@@ -0,0 +1,203 @@
+use std::{error::Error, fmt, ops::RangeInclusive};
+
+use rand::{seq::SliceRandom, Rng};
+
+use crate::{candidates, roll, Level, Species, Weird, Wildmon, WildmonSettings};
+
+/// Species rolled by `wildmon_avoiding()` before it gives up
+const MAX_ROLLS: usize = 100;
+
+/// Every nick `wildmon_avoiding()` came up with was taken
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AllTaken {
+    /// How many times the species was rolled
+    pub rolls: usize,
+}
+
+impl fmt::Display for AllTaken {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "no free nick in {} rolls", self.rolls)
+    }
+}
+
+impl Error for AllTaken {}
+
+/// Roll a wild Pokémon whose nick isn't `taken`, such as one already in use
+/// in a room
+///
+/// If the first roll is free, it's the same Pokémon `wildmon()` would have
+/// given. Otherwise the roll is tried at other levels and with the other
+/// entries of `Species::names` before rolling another species. Levels are
+/// only swapped for ones that wouldn't change which of the species' rules
+/// apply. For a set of nicks, pass `|nick| nicks.contains(nick)`.
+pub fn wildmon_avoiding<'a, R, F>(
+    rng: &mut R,
+    pokedex: &'a [Species],
+    opts: &WildmonSettings,
+    taken: F,
+) -> Result<Wildmon<'a>, AllTaken>
+where
+    R: Rng + ?Sized,
+    F: Fn(&str) -> bool,
+{
+    let candidates = candidates(pokedex, opts);
+    for _ in 0..MAX_ROLLS {
+        let mon = roll(rng, &candidates, &candidates, opts);
+        if let Some(mon) = free_variation(rng, mon, opts, &taken) {
+            return Ok(mon);
+        }
+    }
+    Err(AllTaken { rolls: MAX_ROLLS })
+}
+
+/// The first of `mon` and its variations that isn't taken
+fn free_variation<'a, R, F>(
+    rng: &mut R,
+    mon: Wildmon<'a>,
+    opts: &WildmonSettings,
+    taken: &F,
+) -> Option<Wildmon<'a>>
+where
+    R: Rng + ?Sized,
+    F: Fn(&str) -> bool,
+{
+    if !taken(&mon.to_string()) {
+        return Some(mon);
+    }
+    // Missingno. has nothing to vary
+    let species = mon.species?;
+
+    let mut name_indexes: Vec<usize> = (0..species.names.len())
+        .filter(|&i| i != mon.name_index)
+        .collect();
+    name_indexes.shuffle(rng);
+    name_indexes.insert(0, mon.name_index);
+    let mut levels = other_levels(species, &mon, opts);
+    levels.shuffle(rng);
+    levels.insert(0, mon.level);
+
+    for name_index in name_indexes {
+        let renamed = match renamed(&mon, name_index) {
+            Some(renamed) => renamed,
+            None => continue,
+        };
+        for &level in &levels {
+            let variation = Wildmon {
+                level,
+                ..renamed.clone()
+            };
+            if !taken(&variation.to_string()) {
+                return Some(variation);
+            }
+        }
+    }
+    None
+}
+
+/// `mon` going by another of its species' names
+fn renamed<'a>(mon: &Wildmon<'a>, name_index: usize) -> Option<Wildmon<'a>> {
+    let old = mon.base_name();
+    let new = mon.species?.names.get(name_index)?;
+    // Disguises leave the name they replaced nowhere to be found
+    if !mon.name.contains(old) {
+        return None;
+    }
+    Some(Wildmon {
+        name: mon.name.replacen(old, new, 1),
+        name_index,
+        ..mon.clone()
+    })
+}
+
+/// Levels `mon` could have been rolled at just as well
+fn other_levels(species: &Species, mon: &Wildmon, opts: &WildmonSettings) -> Vec<Level> {
+    let range: RangeInclusive<u8> = match mon.weird {
+        Some(Weird::Baby) => 1..=10,
+        _ => opts.level_range(),
+    };
+    // Glitch levels and Missingno.'s level 180 stay as they are
+    let real = match mon.level.number() {
+        Some(real) if range.contains(&real) => real,
+        _ => return Vec::new(),
+    };
+    let species_name = mon.species_name();
+    let rules: Vec<_> = species
+        .rules
+        .iter()
+        .chain(
+            opts.profiles
+                .iter()
+                .flat_map(|profile| profile.rules_for(species_name)),
+        )
+        .collect();
+    range
+        .filter(|&level| level != real)
+        .filter(|&level| {
+            rules.iter().all(|rule| {
+                let when = &rule.when;
+                when.level_holds(Some(level)) == when.level_holds(Some(real))
+            })
+        })
+        .map(|level| match mon.level {
+            Level::Imaginary(_) => Level::Imaginary(level),
+            Level::Complex(_, imaginary) => Level::Complex(level, imaginary),
+            _ => Level::Normal(level),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{seeded_rng, wildmon, Odds, POKEDEX};
+    use std::collections::HashSet;
+
+    #[test]
+    fn avoids_taken_nicks() {
+        let opts = WildmonSettings::default();
+        let first = wildmon(&mut seeded_rng(5), &POKEDEX, &opts);
+        let free = wildmon_avoiding(&mut seeded_rng(5), &POKEDEX, &opts, |_| false).unwrap();
+        assert_eq!(free, first);
+
+        let taken: HashSet<String> = vec![first.to_string()].into_iter().collect();
+        let mut rng = seeded_rng(5);
+        let free =
+            wildmon_avoiding(&mut rng, &POKEDEX, &opts, |nick| taken.contains(nick)).unwrap();
+        assert!(!taken.contains(&free.to_string()));
+        assert_eq!(free.index, first.index);
+    }
+
+    #[test]
+    fn variations() {
+        let opts = WildmonSettings::builder()
+            .alt_name_odds(Odds::NEVER)
+            .shiny_odds(Odds::NEVER)
+            .level_range(5, 5)
+            .build();
+        let pikachu = &POKEDEX[25..26];
+        let mut rng = seeded_rng(5);
+        let taken = |nick: &str| nick.contains("Pikachu");
+        let free = wildmon_avoiding(&mut rng, pikachu, &opts, taken).unwrap();
+        assert_ne!(free.name_index, 0);
+        assert!(!free.to_string().contains("Pikachu"));
+
+        assert_eq!(
+            wildmon_avoiding(&mut rng, pikachu, &opts, |_| true),
+            Err(AllTaken { rolls: MAX_ROLLS })
+        );
+
+        // Rattata's top percentage is only for levels 96 and up
+        let mon = Wildmon {
+            level: Level::Normal(99),
+            weird: None,
+            ..wildmon(&mut rng, &POKEDEX[19..20], &opts)
+        };
+        let opts = WildmonSettings::builder().level_range(90, 100).build();
+        let levels = other_levels(&POKEDEX[19], &mon, &opts);
+        let expected: Vec<Level> = [96, 97, 98, 100]
+            .iter()
+            .map(|&l| Level::Normal(l))
+            .collect();
+        assert_eq!(levels, expected);
+    }
+}
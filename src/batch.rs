@@ -0,0 +1,292 @@
+use std::{collections::HashSet, error::Error, fmt, str::FromStr};
+
+use rand::Rng;
+
+use crate::{candidates, roll, weird::WEIRD_DIE, Species, Wildmon, WildmonSettings};
+
+/// How different the Pokémon in a batch have to be from each other
+///
+/// Each kind includes the ones before it, so Pokémon of different species
+/// still get different nicks.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Uniqueness {
+    /// Anything goes, as when calling `wildmon()` in a loop
+    None,
+    Nicks,
+    Species,
+    /// No two from the same evolutionary line, like Eevee and Vaporeon
+    Families,
+}
+
+impl Uniqueness {
+    fn plural(self) -> &'static str {
+        match self {
+            Uniqueness::None | Uniqueness::Nicks => "nicks",
+            Uniqueness::Species => "species",
+            Uniqueness::Families => "families",
+        }
+    }
+}
+
+impl FromStr for Uniqueness {
+    type Err = UnknownUniqueness;
+
+    fn from_str(s: &str) -> Result<Uniqueness, UnknownUniqueness> {
+        match s {
+            "none" => Ok(Uniqueness::None),
+            "nicks" => Ok(Uniqueness::Nicks),
+            "species" => Ok(Uniqueness::Species),
+            "families" => Ok(Uniqueness::Families),
+            _ => Err(UnknownUniqueness(s.to_string())),
+        }
+    }
+}
+
+/// A uniqueness name `Uniqueness` doesn't know
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownUniqueness(pub String);
+
+impl fmt::Display for UnknownUniqueness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "unknown uniqueness `{}`; expected none, nicks, species or families",
+            self.0
+        )
+    }
+}
+
+impl Error for UnknownUniqueness {}
+
+/// Why a batch couldn't be rolled
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchError {
+    /// The pokédex, as filtered by the settings, doesn't have enough species
+    /// or families to go around
+    TooFew {
+        uniqueness: Uniqueness,
+        wanted: usize,
+        available: usize,
+    },
+    /// Rolling kept turning up nicks already in the batch
+    GaveUp { wanted: usize, found: usize },
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::TooFew {
+                uniqueness,
+                wanted,
+                available,
+            } => write!(
+                f,
+                "asked for {} different {}, but the pokédex only has {} to roll",
+                wanted,
+                uniqueness.plural(),
+                available
+            ),
+            BatchError::GaveUp { wanted, found } => write!(
+                f,
+                "only found {} different nicks out of the {} asked for",
+                found, wanted
+            ),
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+/// The earliest stage of the evolutionary family at `index` in `pokedex`
+pub(crate) fn family(pokedex: &[Species], mut index: usize) -> usize {
+    // Bounded, in case a custom pokédex evolves in circles
+    for _ in 0..pokedex.len() {
+        match pokedex.get(index).and_then(|species| species.evolves_from) {
+            Some(from) if from < pokedex.len() => index = from,
+            _ => break,
+        }
+    }
+    index
+}
+
+/// More different nicks than `roll()` could ever give `candidates`
+///
+/// Multiplies out every choice a roll makes, so it's far too high for any
+/// real pokédex and only catches counts that couldn't be met in any case.
+fn most_nicks(candidates: &[(usize, &Species)], opts: &WildmonSettings) -> usize {
+    let levels = opts.level_range().len();
+    candidates
+        .iter()
+        .map(|&(_, species)| {
+            let name = species.names.first().map_or("", String::as_str);
+            let profiles = || opts.profiles.iter();
+            let forms = species.forms.len()
+                + profiles()
+                    .map(|profile| profile.forms_for(name).len())
+                    .sum::<usize>();
+            let rules = species.rules.len()
+                + profiles()
+                    .map(|profile| profile.rules_for(name).len())
+                    .sum::<usize>();
+            let disguises = species
+                .disguise
+                .as_ref()
+                .map_or(0, |disguise| disguise.names.len().max(candidates.len()));
+            [
+                species.names.len().max(1),
+                // Genders, and the glitch or 180 of Missingno.
+                3 * 2,
+                // Every face of the weird die, plus the imaginary parts of
+                // complex levels
+                levels.saturating_mul(WEIRD_DIE as usize + 100),
+                // None, Mega or Primal and Mega X or Y, and regional forms
+                3 + species.tags.len(),
+                forms.max(1),
+                1 + disguises,
+                // Each rule applied or not
+                1usize.checked_shl(rules as u32).unwrap_or(usize::MAX),
+                // Shiny and Pokérus
+                4,
+            ]
+            .iter()
+            .fold(1, |product: usize, &choices| {
+                product.saturating_mul(choices)
+            })
+        })
+        .fold(0, usize::saturating_add)
+}
+
+/// Roll `count` wild Pokémon at once, as different as `uniqueness` asks
+///
+/// Species and families are checked up front, so asking for more than the
+/// filtered pokédex has fails straight away with `BatchError::TooFew`; so do
+/// counts of nicks beyond all the pokédex could give. Nicks are rerolled when
+/// they repeat, up to a limit. With `Uniqueness::None`
+/// this rolls the same Pokémon as calling `wildmon()` `count` times.
+pub fn wildmon_batch<'a, R: Rng + ?Sized>(
+    rng: &mut R,
+    pokedex: &'a [Species],
+    opts: &WildmonSettings,
+    count: usize,
+    uniqueness: Uniqueness,
+) -> Result<Vec<Wildmon<'a>>, BatchError> {
+    let candidates = candidates(pokedex, opts);
+    let key = |index: usize| match uniqueness {
+        Uniqueness::Families => family(pokedex, index),
+        _ => index,
+    };
+
+    let too_few = |available: usize| BatchError::TooFew {
+        uniqueness,
+        wanted: count,
+        available,
+    };
+    if uniqueness >= Uniqueness::Species {
+        let keys: HashSet<usize> = candidates.iter().map(|&(index, _)| key(index)).collect();
+        if keys.len() < count {
+            return Err(too_few(keys.len()));
+        }
+    } else if uniqueness == Uniqueness::Nicks {
+        // With nothing to roll, there's still Missingno.
+        let available = most_nicks(&candidates, opts).max(1);
+        if available < count {
+            return Err(too_few(available));
+        }
+    }
+
+    let mut pick_from = candidates.clone();
+    let mut nicks = HashSet::new();
+    let mut mons = Vec::new();
+    let max_attempts = count.saturating_mul(100).saturating_add(1000);
+    for _ in 0..max_attempts {
+        if mons.len() == count {
+            break;
+        }
+        let mon = roll(rng, &pick_from, &candidates, opts);
+        if uniqueness >= Uniqueness::Nicks && !nicks.insert(mon.to_string()) {
+            continue;
+        }
+        if uniqueness >= Uniqueness::Species {
+            let used = key(mon.index);
+            pick_from.retain(|&(index, _)| key(index) != used);
+        }
+        mons.push(mon);
+    }
+
+    if mons.len() < count {
+        return Err(BatchError::GaveUp {
+            wanted: count,
+            found: mons.len(),
+        });
+    }
+    Ok(mons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{seeded_rng, wildmon, Odds, POKEDEX};
+
+    #[test]
+    fn families() {
+        // Eevee, Vaporeon and Glaceon
+        assert_eq!(family(&POKEDEX, 133), 133);
+        assert_eq!(family(&POKEDEX, 134), 133);
+        assert_eq!(family(&POKEDEX, 471), 133);
+        // Pikachu came before Pichu, but Pichu is the first stage
+        assert_eq!(family(&POKEDEX, 26), 172);
+    }
+
+    #[test]
+    fn batches() {
+        let opts = WildmonSettings::default();
+        let mut rng = seeded_rng(7);
+        let mons = wildmon_batch(&mut rng, &POKEDEX, &opts, 5, Uniqueness::None).unwrap();
+        let mut rng = seeded_rng(7);
+        let looped: Vec<Wildmon> = (0..5).map(|_| wildmon(&mut rng, &POKEDEX, &opts)).collect();
+        assert_eq!(mons, looped);
+
+        let mons = wildmon_batch(&mut rng, &POKEDEX, &opts, 200, Uniqueness::Species).unwrap();
+        let species: HashSet<usize> = mons.iter().map(|mon| mon.index).collect();
+        assert_eq!(species.len(), 200);
+
+        // Every family there is, and then one too many
+        let families: HashSet<usize> = candidates(&POKEDEX, &opts)
+            .iter()
+            .map(|&(index, _)| family(&POKEDEX, index))
+            .collect();
+        let count = families.len();
+        let mons = wildmon_batch(&mut rng, &POKEDEX, &opts, count, Uniqueness::Families).unwrap();
+        let rolled: HashSet<usize> = mons.iter().map(|mon| family(&POKEDEX, mon.index)).collect();
+        assert_eq!(rolled, families);
+        assert_eq!(
+            wildmon_batch(&mut rng, &POKEDEX, &opts, count + 1, Uniqueness::Families),
+            Err(BatchError::TooFew {
+                uniqueness: Uniqueness::Families,
+                wanted: count + 1,
+                available: count,
+            })
+        );
+    }
+
+    #[test]
+    fn unique_nicks() {
+        let opts = WildmonSettings::builder()
+            .alt_name_odds(Odds::NEVER)
+            .level_range(5, 5)
+            .build();
+        let mut rng = seeded_rng(7);
+        let mons = wildmon_batch(&mut rng, &POKEDEX[25..26], &opts, 3, Uniqueness::Nicks).unwrap();
+        assert_ne!(mons[0].to_string(), mons[1].to_string());
+        assert!(matches!(
+            wildmon_batch(&mut rng, &POKEDEX[25..26], &opts, 3, Uniqueness::Species),
+            Err(BatchError::TooFew { available: 1, .. })
+        ));
+        assert!(matches!(
+            wildmon_batch(&mut rng, &POKEDEX, &opts, usize::MAX, Uniqueness::Nicks),
+            Err(BatchError::TooFew { .. })
+        ));
+        assert_eq!("families".parse(), Ok(Uniqueness::Families));
+        assert!("genera".parse::<Uniqueness>().is_err());
+    }
+}
@@ -0,0 +1,325 @@
+//! Defaults from a config file and `WILDMON_*` environment variables
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use serde::{de::Error, Deserialize, Deserializer};
+use serde_derive::Deserialize;
+use wildmon::{Format, Gender, Profile, Uniqueness, WildmonSettings};
+
+use crate::{load_profile, parse_value, Cli};
+
+/// Where to look for a config file: `--config`, then `$WILDMON_CONFIG`, then
+/// `wildmon/config.yaml` under the XDG config directory. Only a file named
+/// explicitly has to exist.
+pub fn config_path<E>(explicit: Option<String>, var: E) -> Option<(PathBuf, bool)>
+where
+    E: Fn(&str) -> Option<String>,
+{
+    if let Some(path) = explicit.or_else(|| var("WILDMON_CONFIG")) {
+        return Some((path.into(), true));
+    }
+    let config_home = var("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| var("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some((config_home.join("wildmon").join("config.yaml"), false))
+}
+
+/// What a config file holds: the `WildmonSettings` keys, along with the
+/// options that only the command line has
+#[derive(Deserialize, Debug, Default)]
+#[serde(default)]
+pub struct Config {
+    count: Option<usize>,
+    #[serde(deserialize_with = "from_str")]
+    unique: Option<Uniqueness>,
+    seed: Option<u64>,
+    #[serde(deserialize_with = "from_str")]
+    format: Option<Format>,
+    dex: Option<String>,
+    /// Taken out of `settings` to be checked like the options are
+    min_level: Option<u8>,
+    max_level: Option<u8>,
+    /// Taken out of `settings` so names can be files, as with `--profile`
+    profiles: Option<Vec<ProfileEntry>>,
+    #[serde(flatten)]
+    settings: WildmonSettings,
+    /// Whatever neither of the above took; flattening keeps
+    /// `WildmonSettings` from rejecting unknown keys itself
+    #[serde(flatten)]
+    unknown: BTreeMap<String, serde_yaml::Value>,
+}
+
+/// A profile in a config file: a built-in one or a file by name, or written
+/// out in place
+#[derive(Deserialize, Debug)]
+#[serde(untagged)]
+enum ProfileEntry {
+    Name(String),
+    Inline(Profile),
+}
+
+/// Options written as they would be on the command line
+fn from_str<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    String::deserialize(deserializer)?
+        .parse()
+        .map(Some)
+        .map_err(D::Error::custom)
+}
+
+impl Config {
+    pub fn from_yaml(yaml: &str) -> Result<Config, serde_yaml::Error> {
+        let config: Config = serde_yaml::from_str(yaml)?;
+        match config.unknown.keys().next() {
+            Some(key) => Err(serde_yaml::Error::custom(format!(
+                "unknown field `{}`",
+                key
+            ))),
+            None => Ok(config),
+        }
+    }
+
+    /// Put the file's options in `cli`, keeping what the file leaves out
+    fn apply(self, mut cli: Cli) -> Result<Cli, String> {
+        cli.settings = self.settings;
+        let levels = cli.settings.level_range();
+        let min = self.min_level.unwrap_or(*levels.start());
+        let max = self.max_level.unwrap_or(*levels.end());
+        if min == 0 || max == 0 {
+            return Err("min_level, max_level: levels start at 1".to_string());
+        }
+        if min > max {
+            return Err(format!("min_level {} is above max_level {}", min, max));
+        }
+        cli.settings.set_level_range(min, max);
+        if let Some(entries) = self.profiles {
+            let profiles = entries
+                .into_iter()
+                .map(|entry| match entry {
+                    ProfileEntry::Name(name) => load_profile(&name),
+                    ProfileEntry::Inline(profile) => Ok(profile),
+                })
+                .collect::<Result<_, _>>()?;
+            cli.settings.set_profiles(profiles);
+        }
+        cli.count = self.count.unwrap_or(cli.count);
+        cli.unique = self.unique.unwrap_or(cli.unique);
+        cli.seed = self.seed.or(cli.seed);
+        cli.format = self.format.unwrap_or(cli.format);
+        cli.dex = self.dex.or(cli.dex);
+        Ok(cli)
+    }
+}
+
+/// Read a config file on top of `cli`
+pub fn load_config(path: &PathBuf, required: bool, cli: Cli) -> Result<Cli, String> {
+    let yaml = match std::fs::read_to_string(path) {
+        Ok(yaml) => yaml,
+        Err(_) if !required && !path.exists() => return Ok(cli),
+        Err(err) => return Err(format!("can't read config {:?}: {}", path, err)),
+    };
+    let config =
+        Config::from_yaml(&yaml).map_err(|err| format!("invalid config {:?}: {}", path, err))?;
+    config
+        .apply(cli)
+        .map_err(|err| format!("invalid config {:?}: {}", path, err))
+}
+
+fn parse_bool(name: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!(
+            "invalid value `{}` for {}: expected true or false",
+            value, name
+        )),
+    }
+}
+
+fn parse_gender(name: &str, value: &str) -> Result<Gender, String> {
+    match value.trim() {
+        "m" | "male" => Ok(Gender::Male),
+        "f" | "female" => Ok(Gender::Female),
+        "ag" | "agender" => Ok(Gender::Agender),
+        other => Err(format!(
+            "invalid value `{}` for {}: expected male, female or agender",
+            other, name
+        )),
+    }
+}
+
+/// Apply `WILDMON_*` variables on top of whatever `cli` already holds
+pub fn apply_env<E>(mut cli: Cli, var: E) -> Result<Cli, String>
+where
+    E: Fn(&str) -> Option<String>,
+{
+    let settings = &mut cli.settings;
+    if let Some(value) = var("WILDMON_WHITESPACE") {
+        settings.set_whitespace(parse_bool("WILDMON_WHITESPACE", &value)?);
+    }
+    if let Some(value) = var("WILDMON_CANON") {
+        settings.set_canon(parse_bool("WILDMON_CANON", &value)?);
+    }
+    if let Some(value) = var("WILDMON_GENDERS") {
+        let genders = value
+            .split(',')
+            .filter(|gender| !gender.trim().is_empty())
+            .map(|gender| parse_gender("WILDMON_GENDERS", gender))
+            .collect::<Result<_, _>>()?;
+        settings.set_allowed_genders(genders);
+    }
+    if let Some(value) = var("WILDMON_TEMPLATE") {
+        settings.set_template(parse_value("WILDMON_TEMPLATE", &value)?);
+    }
+    if let Some(value) = var("WILDMON_MONOGENDER_SYMBOL") {
+        settings.set_monogender_uses_symbol(parse_bool("WILDMON_MONOGENDER_SYMBOL", &value)?);
+    }
+    let levels = settings.level_range();
+    let min = match var("WILDMON_MIN_LEVEL") {
+        Some(value) => parse_value("WILDMON_MIN_LEVEL", &value)?,
+        None => *levels.start(),
+    };
+    let max = match var("WILDMON_MAX_LEVEL") {
+        Some(value) => parse_value("WILDMON_MAX_LEVEL", &value)?,
+        None => *levels.end(),
+    };
+    if min == 0 || max == 0 {
+        return Err("WILDMON_MIN_LEVEL, WILDMON_MAX_LEVEL: levels start at 1".to_string());
+    }
+    if min > max {
+        return Err(format!(
+            "WILDMON_MIN_LEVEL {} is above WILDMON_MAX_LEVEL {}",
+            min, max
+        ));
+    }
+    settings.set_level_range(min, max);
+    if let Some(value) = var("WILDMON_PROFILES") {
+        let profiles = value
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| load_profile(name.trim()))
+            .collect::<Result<_, _>>()?;
+        settings.set_profiles(profiles);
+    }
+
+    if let Some(value) = var("WILDMON_COUNT") {
+        cli.count = parse_value("WILDMON_COUNT", &value)?;
+    }
+    if let Some(value) = var("WILDMON_UNIQUE") {
+        cli.unique = value
+            .parse()
+            .map_err(|err| format!("WILDMON_UNIQUE: {}", err))?;
+    }
+    if let Some(value) = var("WILDMON_SEED") {
+        cli.seed = Some(parse_value("WILDMON_SEED", &value)?);
+    }
+    if let Some(value) = var("WILDMON_FORMAT") {
+        cli.format = value
+            .parse()
+            .map_err(|err| format!("WILDMON_FORMAT: {}", err))?;
+    }
+    if let Some(value) = var("WILDMON_DEX") {
+        cli.dex = Some(value);
+    }
+
+    Ok(cli)
+}
+
+/// The real environment, ignoring variables that aren't valid Unicode
+pub fn real_env(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn config_locations() {
+        let (path, required) = config_path(None, env_of(&[("HOME", "/home/ash")])).unwrap();
+        assert_eq!(path, PathBuf::from("/home/ash/.config/wildmon/config.yaml"));
+        assert!(!required);
+
+        let xdg = env_of(&[("HOME", "/home/ash"), ("XDG_CONFIG_HOME", "/etc/xdg")]);
+        let (path, _) = config_path(None, xdg).unwrap();
+        assert_eq!(path, PathBuf::from("/etc/xdg/wildmon/config.yaml"));
+
+        let (path, required) = config_path(Some("my.yaml".into()), env_of(&[])).unwrap();
+        assert_eq!(path, PathBuf::from("my.yaml"));
+        assert!(required);
+    }
+
+    #[test]
+    fn config_file_keys() {
+        let yaml = "count: 3\nunique: species\nformat: ndjson\nseed: 7\nprofiles: [rp]\n";
+        let config = Config::from_yaml(yaml).unwrap();
+        let cli = config.apply(Cli::default()).unwrap();
+        assert_eq!(cli.count, 3);
+        assert_eq!(cli.unique, Uniqueness::Species);
+        assert_eq!(cli.format, Format::Ndjson);
+        assert_eq!(cli.seed, Some(7));
+        assert!(cli.settings.has_profile("rp"));
+        assert_eq!(cli.dex, None);
+
+        let err = Config::from_yaml("format: xml").unwrap_err();
+        assert!(err.to_string().contains("unknown output format"), "{}", err);
+        let err = Config::from_yaml("cuont: 3").unwrap_err();
+        assert!(err.to_string().contains("unknown field `cuont`"), "{}", err);
+        let zero = Config::from_yaml("min_level: 0").unwrap();
+        assert!(zero.apply(Cli::default()).is_err());
+        let upside_down = Config::from_yaml("min_level: 50\nmax_level: 10").unwrap();
+        assert!(upside_down.apply(Cli::default()).is_err());
+
+        // Profiles are found the same way as for --profile
+        let path = env::temp_dir().join(format!("wildmon-profile-{}.yaml", std::process::id()));
+        std::fs::write(&path, "name: mine\n").unwrap();
+        let yaml = format!("profiles: [rp, {:?}, {{name: inline}}]", path);
+        let cli = Config::from_yaml(&yaml).unwrap().apply(Cli::default());
+        std::fs::remove_file(&path).unwrap();
+        let cli = cli.unwrap();
+        for name in &["rp", "mine", "inline"] {
+            assert!(cli.settings.has_profile(name), "{}", name);
+        }
+
+        let err = Config::from_yaml("min_level: high").unwrap_err();
+        assert!(err.to_string().contains("expected u8"), "{}", err);
+    }
+
+    #[test]
+    fn environment_overrides() {
+        let vars = env_of(&[
+            ("WILDMON_COUNT", "4"),
+            ("WILDMON_CANON", "no"),
+            ("WILDMON_GENDERS", "male, agender"),
+            ("WILDMON_MAX_LEVEL", "20"),
+            ("WILDMON_PROFILES", "rp"),
+        ]);
+        let cli = apply_env(Cli::default(), vars).unwrap();
+        assert_eq!(cli.count, 4);
+        assert_eq!(cli.settings.level_range(), 1..=20);
+        assert!(cli.settings.has_profile("rp"));
+
+        assert!(apply_env(Cli::default(), env_of(&[("WILDMON_CANON", "maybe")])).is_err());
+        assert!(apply_env(Cli::default(), env_of(&[("WILDMON_GENDERS", "x")])).is_err());
+        assert!(apply_env(Cli::default(), env_of(&[("WILDMON_TEMPLATE", "{x}")])).is_err());
+        assert!(apply_env(Cli::default(), env_of(&[("WILDMON_MIN_LEVEL", "0")])).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::process::exit;
+use std::str::FromStr;
+
+use rand::{thread_rng, RngCore};
+use wildmon::{
+    parse_pokedex, seeded_rng, wildmon, wildmon_batch, write_mons, Format, Gender, Profile,
+    Species, Uniqueness, WildmonSettings,
+};
+
+mod config;
+
+const USAGE: &str = "\
+Usage: wildmon [OPTIONS]
+
+Generate wild Pokémon nicks, one per line, or as JSON or YAML records.
+
+Options:
+  -n, --count <N>        Generate N nicks [default: 1]
+  -u, --unique <KIND>    Make the nicks differ: none, nicks, species or
+                         families (evolutionary lines) [default: none]
+  -s, --seed <SEED>      Seed the generator for repeatable output; the same
+                         seed gives the same nicks on any machine
+  -w, --whitespace       Keep spaces instead of replacing them with underscores
+      --canon            Only roll canon Pokémon [default]
+      --no-canon         Also roll glitches, fakemon and other oddities
+  -m, --male             Allow male Pokémon
+  -f, --female           Allow female Pokémon
+      --agender          Allow genderless Pokémon
+      --min-level <N>    Lowest level to roll [default: 1]
+      --max-level <N>    Highest level to roll [default: 100]
+      --format <FORMAT>  Output format: text, json, yaml or ndjson [default: text]
+      --template <TEMPLATE>
+                         Lay out nicks with placeholders {prefix}, {name},
+                         {gender}, {level}, {suffix} and {dex} (or {dex:03});
+                         its spaces become underscores too without --whitespace
+                         [default: \"{prefix} {name}{gender}{suffix} (lv{level})\"]
+      --no-monogender-symbol
+                         Leave out ♂ and ♀ for single-gender species
+      --dex <FILE>       Roll from a pokédex YAML file instead of the built-in one
+      --profile <NAME>   Enable a built-in profile or a profile file; repeatable
+      --config <FILE>    Read settings from FILE instead of the default config
+  -h, --help             Print this help
+
+If no gender is allowed explicitly, all of them are.
+The old single-word gender arguments m, f and ag are still accepted.
+
+Settings are merged from, in increasing order of precedence:
+  1. built-in defaults
+  2. a YAML config file: --config, else $WILDMON_CONFIG, else
+     $XDG_CONFIG_HOME/wildmon/config.yaml (~/.config/wildmon/config.yaml)
+  3. environment variables: WILDMON_COUNT, WILDMON_UNIQUE, WILDMON_SEED,
+     WILDMON_WHITESPACE, WILDMON_CANON, WILDMON_GENDERS (comma-separated),
+     WILDMON_MIN_LEVEL, WILDMON_MAX_LEVEL, WILDMON_FORMAT, WILDMON_DEX,
+     WILDMON_PROFILES (comma-separated), WILDMON_TEMPLATE,
+     WILDMON_MONOGENDER_SYMBOL
+  4. command-line options
+Gender and profile lists from a later source replace earlier ones.
+
+The config file takes the keys count, unique, seed, format and dex, with the
+same values as the options, and the settings algorithm, canon, whitespace,
+allow_genders, min_level, max_level, shiny_odds, pokerus_odds, mega_odds,
+regional_odds, alt_name_odds, profiles, template and monogender_uses_symbol.
+Odds are written as [chances, out_of], like [1, 4096]. Profiles are named as
+with --profile, or written out in place.";
+
+/// Everything the command line can ask for
+#[derive(Debug)]
+pub struct Cli {
+    settings: WildmonSettings,
+    count: usize,
+    unique: Uniqueness,
+    seed: Option<u64>,
+    format: Format,
+    dex: Option<String>,
+    config: Option<String>,
+    help: bool,
+}
+
+impl Default for Cli {
+    fn default() -> Self {
+        Cli {
+            settings: WildmonSettings::default(),
+            count: 1,
+            unique: Uniqueness::None,
+            seed: None,
+            format: Format::Text,
+            dex: None,
+            config: None,
+            help: false,
+        }
+    }
+}
+
+/// Find a built-in profile by name, or else load one from a file
+pub fn load_profile(name: &str) -> Result<Profile, String> {
+    if let Some(profile) = Profile::builtin(name) {
+        return Ok(profile);
+    }
+    let yaml = std::fs::read_to_string(name)
+        .map_err(|err| format!("no built-in profile or readable file {:?}: {}", name, err))?;
+    Profile::from_yaml(&yaml).map_err(|err| format!("invalid profile {:?}: {}", name, err))
+}
+
+pub fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, String>
+where
+    T::Err: Display,
+{
+    value
+        .parse()
+        .map_err(|err| format!("invalid value `{}` for {}: {}", value, flag, err))
+}
+
+fn parse_args<I: IntoIterator<Item = String>>(args: I, mut cli: Cli) -> Result<Cli, String> {
+    let mut args = args.into_iter();
+    let mut min_level = None;
+    let mut max_level = None;
+    let mut genders = Vec::new();
+    let mut profiles = Vec::new();
+
+    while let Some(arg) = args.next() {
+        // Accept both "--flag value" and "--flag=value"
+        let (flag, mut inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => {
+                (flag.to_string(), Some(value.to_string()))
+            }
+            _ => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .take()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{} needs a value", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => cli.help = true,
+            "-n" | "--count" => cli.count = parse_value(&flag, &value()?)?,
+            "-u" | "--unique" => cli.unique = parse_value(&flag, &value()?)?,
+            "-s" | "--seed" => cli.seed = Some(parse_value(&flag, &value()?)?),
+            "-w" | "--whitespace" => cli.settings.set_whitespace(true),
+            "--canon" => cli.settings.set_canon(true),
+            "--no-canon" => cli.settings.set_canon(false),
+            "-m" | "--male" | "m" => genders.push(Gender::Male),
+            "-f" | "--female" | "f" => genders.push(Gender::Female),
+            "--agender" | "ag" => genders.push(Gender::Agender),
+            "--min-level" => min_level = Some(parse_value(&flag, &value()?)?),
+            "--max-level" => max_level = Some(parse_value(&flag, &value()?)?),
+            "--template" => cli.settings.set_template(parse_value(&flag, &value()?)?),
+            "--no-monogender-symbol" => cli.settings.set_monogender_uses_symbol(false),
+            "--format" => cli.format = value()?.parse().map_err(|err| format!("{}", err))?,
+            "--dex" => cli.dex = Some(value()?),
+            "--profile" => profiles.push(load_profile(&value()?)?),
+            "--config" => cli.config = Some(value()?),
+            _ => return Err(format!("unknown argument `{}`", flag)),
+        }
+        if inline.is_some() {
+            return Err(format!("{} doesn't take a value", flag));
+        }
+    }
+
+    if !genders.is_empty() {
+        cli.settings.set_allowed_genders(genders);
+    }
+    if !profiles.is_empty() {
+        cli.settings.set_profiles(profiles);
+    }
+    if min_level.is_some() || max_level.is_some() {
+        let levels = cli.settings.level_range();
+        let min = min_level.unwrap_or(*levels.start());
+        let max = max_level.unwrap_or(*levels.end());
+        if min == 0 || max == 0 {
+            return Err("levels start at 1".to_string());
+        }
+        if min > max {
+            return Err(format!("--min-level {} is above --max-level {}", min, max));
+        }
+        cli.settings.set_level_range(min, max);
+    }
+
+    Ok(cli)
+}
+
+/// Merge defaults, config file, environment and arguments, in that order
+fn load_cli<E>(args: Vec<String>, var: E) -> Result<Cli, String>
+where
+    E: Fn(&str) -> Option<String> + Copy,
+{
+    // A first pass to find --config, which has to be read before the rest,
+    // and --help, which shouldn't depend on the config being any good
+    let first = parse_args(args.clone(), Cli::default())?;
+    if first.help {
+        return Ok(first);
+    }
+    let mut cli = Cli::default();
+    if let Some((path, required)) = config::config_path(first.config, var) {
+        cli = config::load_config(&path, required, cli)?;
+    }
+    let cli = config::apply_env(cli, var)?;
+    parse_args(args, cli)
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let custom_dex: Vec<Species>;
+    let pokedex: &[Species] = match &cli.dex {
+        Some(path) => {
+            let yaml = std::fs::read_to_string(path)
+                .map_err(|err| format!("can't read pokédex {:?}: {}", path, err))?;
+            custom_dex = parse_pokedex(&yaml)
+                .map_err(|err| format!("invalid pokédex {:?}: {}", path, err))?;
+            &custom_dex
+        }
+        None => cli.settings.algorithm().pokedex(),
+    };
+
+    let mut rng: Box<dyn RngCore> = match cli.seed {
+        Some(seed) => Box::new(seeded_rng(seed)),
+        None => Box::new(thread_rng()),
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let written = match cli.unique {
+        Uniqueness::None => {
+            let mons = (0..cli.count).map(|_| wildmon(&mut rng, pokedex, &cli.settings));
+            write_mons(&mut out, cli.format, mons)
+        }
+        // Nothing is written unless the whole batch can be rolled
+        unique => {
+            let mons = wildmon_batch(&mut rng, pokedex, &cli.settings, cli.count, unique)
+                .map_err(|err| err.to_string())?;
+            write_mons(&mut out, cli.format, mons)
+        }
+    };
+    match written.and_then(|()| out.flush()) {
+        // Whoever was reading has seen enough
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(format!("can't write output: {}", err)),
+        Ok(()) => Ok(()),
+    }
+}
+
+pub fn main() {
+    let cli = match load_cli(std::env::args().skip(1).collect(), config::real_env) {
+        Ok(cli) => cli,
+        Err(err) => {
+            eprintln!(
+                "wildmon: {}\nTry `wildmon --help` for more information.",
+                err
+            );
+            exit(2);
+        }
+    };
+
+    if cli.help {
+        println!("{}", USAGE);
+        return;
+    }
+
+    if let Err(err) = run(cli) {
+        eprintln!("wildmon: {}", err);
+        exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()), Cli::default())
+    }
+
+    #[test]
+    fn arguments() {
+        let cli = parse(&["-n", "3", "--seed=42", "--min-level", "5", "f"]).unwrap();
+        assert_eq!(cli.count, 3);
+        assert_eq!(cli.seed, Some(42));
+
+        assert_eq!(
+            parse(&["--bogus"]).unwrap_err(),
+            "unknown argument `--bogus`"
+        );
+        assert_eq!(parse(&["--count"]).unwrap_err(), "--count needs a value");
+        assert!(parse(&["--count", "many"]).is_err());
+        assert!(parse(&["--min-level", "50", "--max-level", "10"]).is_err());
+        assert!(parse(&["--min-level", "0"]).is_err());
+        assert!(parse(&["--whitespace=yes"]).is_err());
+        assert!(parse(&["--format", "xml"]).is_err());
+        assert_eq!(parse(&["--format=ndjson"]).unwrap().format, Format::Ndjson);
+        assert_eq!(
+            parse(&["-u", "families"]).unwrap().unique,
+            Uniqueness::Families
+        );
+        assert!(parse(&["--unique", "genus"]).is_err());
+        assert!(parse(&["--template", "{name"]).is_err());
+    }
+
+    #[test]
+    fn help_skips_the_config() {
+        let broken = |name: &str| match name {
+            "WILDMON_CONFIG" => Some("/nonexistent/wildmon.yaml".to_string()),
+            _ => None,
+        };
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect();
+        assert!(load_cli(args(&["--help"]), broken).unwrap().help);
+        assert!(load_cli(args(&["-n", "2"]), broken).is_err());
+    }
+
+    #[test]
+    fn arguments_override_lists() {
+        let mut base = Cli::default();
+        base.settings.allow_gender(Gender::Female);
+        base.settings.set_level_range(10, 20);
+        let args = ["-m", "--max-level", "30", "--config", "x.yaml"];
+        let cli = parse_args(args.iter().map(|arg| arg.to_string()), base).unwrap();
+        assert_eq!(cli.settings.level_range(), 10..=30);
+
+        let expected = WildmonSettings::builder()
+            .allow_gender(Gender::Male)
+            .level_range(10, 30)
+            .build();
+        assert_eq!(cli.settings, expected);
+        assert_eq!(cli.config, Some("x.yaml".into()));
+
+        // A value that happens to look like --config isn't one
+        let cli = parse(&["--template", "--config", "--config=y.yaml"]).unwrap();
+        assert_eq!(cli.config, Some("y.yaml".into()));
+    }
+}
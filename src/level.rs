@@ -0,0 +1,106 @@
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+use serde_derive::Serialize;
+
+/// A Pokémon's level, which isn't always a plain number
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Level {
+    Normal(u8),
+    /// Rendered as "15i"
+    Imaginary(u8),
+    /// Real and imaginary parts, rendered as "15+42i"
+    Complex(u8, u8),
+    /// Rendered as "∞"
+    Infinite,
+    /// A garbage character in place of a number, as Missingno. is wont to show
+    Glitch(char),
+}
+
+impl Level {
+    /// Garbage level for a glitch Pokémon, picked by the real level
+    pub fn glitch(level: u8) -> Level {
+        Level::Glitch(char::from(32 + level % 17))
+    }
+
+    /// The real, finite part of the level, if there is one
+    pub fn number(&self) -> Option<u8> {
+        match self {
+            Level::Normal(level) | Level::Imaginary(level) | Level::Complex(level, _) => {
+                Some(*level)
+            }
+            Level::Infinite | Level::Glitch(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Level::Normal(level) => write!(f, "{}", level),
+            Level::Imaginary(level) => write!(f, "{}i", level),
+            Level::Complex(real, imaginary) => write!(f, "{}+{}i", real, imaginary),
+            Level::Infinite => write!(f, "∞"),
+            Level::Glitch(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// Normal levels are serialized as plain numbers and the rest as maps tagged
+/// with their `kind`, like `{"kind": "complex", "real": 15, "imaginary": 42}`
+impl Serialize for Level {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let level = match *self {
+            Level::Normal(level) => return serializer.serialize_u8(level),
+            Level::Imaginary(imaginary) => Tagged::Imaginary { imaginary },
+            Level::Complex(real, imaginary) => Tagged::Complex { real, imaginary },
+            Level::Infinite => Tagged::Infinite,
+            Level::Glitch(character) => Tagged::Glitch { character },
+        };
+        level.serialize(serializer)
+    }
+}
+
+/// The levels that aren't plain numbers, as serialized
+#[derive(Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum Tagged {
+    Imaginary { imaginary: u8 },
+    Complex { real: u8, imaginary: u8 },
+    Infinite,
+    Glitch { character: char },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn level_rendering() {
+        assert_eq!(Level::Normal(15).to_string(), "15");
+        assert_eq!(Level::Imaginary(15).to_string(), "15i");
+        assert_eq!(Level::Complex(15, 42).to_string(), "15+42i");
+        assert_eq!(Level::Infinite.to_string(), "∞");
+        assert_eq!(Level::glitch(17).to_string(), " ");
+        assert_eq!(Level::glitch(50).to_string(), "0");
+    }
+
+    #[test]
+    fn level_serialization() {
+        let json = |level: Level| serde_json::to_string(&level).unwrap();
+        assert_eq!(json(Level::Normal(15)), "15");
+        assert_eq!(
+            json(Level::Imaginary(15)),
+            r#"{"kind":"imaginary","imaginary":15}"#
+        );
+        assert_eq!(
+            json(Level::Complex(16, 54)),
+            r#"{"kind":"complex","real":16,"imaginary":54}"#
+        );
+        assert_eq!(json(Level::Infinite), r#"{"kind":"infinite"}"#);
+        assert_eq!(
+            json(Level::Glitch('0')),
+            r#"{"kind":"glitch","character":"0"}"#
+        );
+    }
+}
@@ -1,23 +1,142 @@
 use once_cell::sync::Lazy;
 use rand::{seq::SliceRandom, Rng};
 use serde_derive::{Deserialize, Serialize};
-use serde_yaml;
 
+mod algorithm;
+mod avoid;
+mod batch;
+mod level;
+mod nick;
+mod output;
+mod parse;
+mod profile;
+mod rng;
+mod rules;
+mod settings;
+mod sticky;
+mod template;
+mod weird;
+
+pub use algorithm::Algorithm;
+pub use avoid::{wildmon_avoiding, AllTaken};
+pub use batch::{wildmon_batch, BatchError, Uniqueness, UnknownUniqueness};
+pub use level::Level;
+pub use profile::Profile;
+pub use rng::{seeded_rng, WildmonRng};
+pub use rules::{Condition, Rule};
+pub use settings::{WildmonSettings, WildmonSettingsBuilder};
+pub use sticky::{Rotation, StickyNicks};
+pub use template::{Template, TemplateError, DEFAULT_TEMPLATE, MAX_DEX_WIDTH};
+pub use weird::Weird;
+
+pub use nick::Wildmon;
+pub use output::{write_mons, Format, Script, UnknownFormat};
+pub use parse::{parse_nick, ParseNickError};
+
+/// The built-in pokédex, as of `Algorithm::LATEST`; older versions have
+/// theirs in `Algorithm::pokedex()`
 pub static POKEDEX: Lazy<Vec<Species>> = Lazy::new(|| {
-    serde_yaml::from_str(include_str!("data/species.yaml")).expect("Parsing embedded YAML pokédex")
+    parse_pokedex(include_str!("data/v1/species.yaml")).expect("Parsing embedded YAML pokédex")
 });
 
-pub const MISSINGNO: &'static str = "Missingno.";
+/// Parse a pokédex in the same format as the embedded one
+pub fn parse_pokedex(yaml: &str) -> Result<Vec<Species>, serde_yaml::Error> {
+    serde_yaml::from_str(yaml)
+}
+
+pub const MISSINGNO: &str = "Missingno.";
 
 /// The shape of the new Pokémon list format
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub struct Species {
     pub names: Vec<String>,
+
+    /// National Pokédex number, for species that have one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub number: Option<u16>,
+
     pub gender: Gender,
 
+    #[serde(skip_serializing_if = "Category::is_canon")]
+    #[serde(default)]
+    pub category: Category,
+
+    /// Index of the species this one evolves from, in the same pokédex
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub evolves_from: Option<usize>,
+
     #[serde(skip_serializing_if = "Vec::is_empty")]
     #[serde(default = "Vec::new")]
     pub tags: Vec<SpeciesTag>,
+
+    /// Replaces the whole prefix when this species comes up shiny
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub shiny_prefix: Option<String>,
+
+    /// Alternate forms, one of which is picked by weight for every roll
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default = "Vec::new")]
+    pub forms: Vec<Form>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub disguise: Option<Disguise>,
+
+    /// Jokes applied in order before the shiny roll
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default = "Vec::new")]
+    pub rules: Vec<Rule>,
+}
+
+/// Where an entry in the pokédex comes from
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
+pub enum Category {
+    /// A real Pokémon from the games
+    #[default]
+    Canon,
+    /// Missingno. and friends
+    Glitch,
+    /// Fan-made Pokémon
+    Fakemon,
+    /// Anything else that can show up in the tall grass, like a Substitute
+    Special,
+}
+
+impl Category {
+    pub fn is_canon(&self) -> bool {
+        *self == Category::Canon
+    }
+}
+
+/// A weighted alternate form of a species
+///
+/// `{}` in the template is replaced with the species name, so "Heat {}"
+/// gives "Heat Rotom"; a template without `{}` replaces the name outright.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Form {
+    #[serde(default = "Form::base_template")]
+    pub template: String,
+
+    #[serde(default = "Form::default_weight")]
+    pub weight: u32,
+}
+
+impl Form {
+    fn base_template() -> String {
+        "{}".into()
+    }
+
+    fn default_weight() -> u32 {
+        1
+    }
+
+    pub fn apply(&self, name: &str) -> String {
+        self.template.replace("{}", name)
+    }
 }
 
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -53,54 +172,139 @@ impl Gender {
     }
 }
 
+/// Borrowing another species' name, like Zoroark's Illusion
+///
+/// In the template, `{}` is replaced with the species' own name and
+/// `{disguise}` with the borrowed one.
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Disguise {
+    pub template: String,
+
+    #[serde(default = "Disguise::default_chance")]
+    pub chance: Odds,
+
+    /// Names to pick the disguise from; when empty, the first name of any
+    /// species in the pokédex being rolled from is borrowed instead
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    #[serde(default = "Vec::new")]
+    pub names: Vec<String>,
+}
+
+impl Disguise {
+    fn default_chance() -> Odds {
+        Odds::ALWAYS
+    }
+
+    pub fn apply(&self, name: &str, disguise: &str) -> String {
+        self.template
+            .replace("{disguise}", disguise)
+            .replace("{}", name)
+    }
+}
+
 /// Tags indicating a species is eligable for certain specific modifiers
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 pub enum SpeciesTag {
     Mega,
+    /// Mega evolves into separate X and Y forms
     MegaXY,
+    /// Undergoes Primal Reversion instead of Mega Evolution
+    Primal,
     AlolaForm,
+    GalarForm,
+    HisuiForm,
+    PaldeaForm,
 }
 
-#[derive(Debug, Clone)]
-pub struct WildmonSettings {
-    canon: bool,
-    whitespace: bool,
-    allow_genders: Vec<Gender>,
-}
-
-impl Default for WildmonSettings {
-    fn default() -> Self {
-        WildmonSettings {
-            canon: true,
-            whitespace: false,
-            allow_genders: Vec::new(),
+impl SpeciesTag {
+    /// Adjective naming the regional form this tag marks, if any
+    pub fn regional_form(&self) -> Option<&'static str> {
+        match self {
+            SpeciesTag::AlolaForm => Some("Alolan"),
+            SpeciesTag::GalarForm => Some("Galarian"),
+            SpeciesTag::HisuiForm => Some("Hisuian"),
+            SpeciesTag::PaldeaForm => Some("Paldean"),
+            _ => None,
         }
     }
 }
 
-impl WildmonSettings {
-    pub fn allow_gender(&mut self, gender: Gender) {
-        self.allow_genders.push(gender);
+/// A chance of `.0` in `.1`, e.g. `Odds(1, 4096)` for shininess
+#[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct Odds(pub u32, pub u32);
+
+impl Odds {
+    pub const NEVER: Odds = Odds(0, 1);
+    pub const ALWAYS: Odds = Odds(1, 1);
+
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> bool {
+        self.1 > 0 && rng.gen_range(0..self.1) < self.0
     }
 }
 
 static DEFAULT_GENDERS: &[Gender] = &[Gender::Male, Gender::Female, Gender::Agender];
 
-pub fn wildmon<R: Rng + ?Sized>(
+/// The species `wildmon()` can roll, along with their indexes in `pokedex`
+pub(crate) fn candidates<'a>(
+    pokedex: &'a [Species],
+    opts: &WildmonSettings,
+) -> Vec<(usize, &'a Species)> {
+    pokedex
+        .iter()
+        .enumerate()
+        .filter(|(_, species)| !opts.canon || species.category.is_canon())
+        .collect()
+}
+
+/// Roll a wild Pokémon from `pokedex`; display the result for its nick
+///
+/// How draws from `rng` become a Pokémon is fixed by `opts`' `Algorithm`,
+/// and anything changing it here has to be put behind a new version. To keep
+/// the nicks of stored seeds, roll from that version's `Algorithm::pokedex()`.
+pub fn wildmon<'a, R: Rng + ?Sized>(
+    rng: &mut R,
+    pokedex: &'a [Species],
+    opts: &WildmonSettings,
+) -> Wildmon<'a> {
+    let candidates = candidates(pokedex, opts);
+    roll(rng, &candidates, &candidates, opts)
+}
+
+/// `wildmon()`, with the species picked from `pick_from`; disguises still
+/// borrow from all of `candidates`
+pub(crate) fn roll<'a, R: Rng + ?Sized>(
     rng: &mut R,
-    pokedex: &Vec<Species>,
+    pick_from: &[(usize, &'a Species)],
+    candidates: &[(usize, &'a Species)],
     opts: &WildmonSettings,
-) -> String {
-    drop(opts.canon);
+) -> Wildmon<'a> {
+    match opts.algorithm {
+        Algorithm::V1 => roll_v1(rng, pick_from, candidates, opts),
+    }
+}
 
-    let species = match pokedex.choose(rng) {
-        Some(species) => species,
-        None => return MISSINGNO.into(),
+/// `roll()` as of `Algorithm::V1`
+fn roll_v1<'a, R: Rng + ?Sized>(
+    rng: &mut R,
+    pick_from: &[(usize, &'a Species)],
+    candidates: &[(usize, &'a Species)],
+    opts: &WildmonSettings,
+) -> Wildmon<'a> {
+    let (index, species) = match pick_from.choose(rng) {
+        Some(&candidate) => candidate,
+        None => return Wildmon::missingno(opts.whitespace),
     };
-    let name = match species.names.first() {
-        Some(name) => name.as_ref(),
-        None => MISSINGNO,
+    let name_index = if species.names.len() > 1 && opts.alt_name_odds.roll(rng) {
+        rng.gen_range(0..species.names.len())
+    } else {
+        0
     };
+    let mut name = match species.names.get(name_index) {
+        Some(name) => name.to_string(),
+        None => MISSINGNO.to_string(),
+    };
+    let species_name = species.names.first().map_or(MISSINGNO, String::as_str);
 
     let allowed_genders = match opts.allow_genders.len() {
         0 => DEFAULT_GENDERS,
@@ -115,26 +319,157 @@ pub fn wildmon<R: Rng + ?Sized>(
             .unwrap_or(Gender::Agender);
     }
 
-    let level = rng.gen_range(1..=100);
+    let level_roll: u8 = rng.gen_range(opts.level_range());
+
+    let weird = Weird::roll(rng);
+    let mut level = match weird {
+        Some(Weird::Baby) => Level::Normal((level_roll % 10) + 1),
+        Some(Weird::Imaginary) => Level::Imaginary(level_roll),
+        Some(Weird::Complex) => Level::Complex(level_roll, rng.gen_range(0..100)),
+        Some(Weird::Omnipotent) => Level::Infinite,
+        _ => Level::Normal(level_roll),
+    };
+    if species_name == MISSINGNO {
+        level = if rng.gen() {
+            Level::glitch(level_roll)
+        } else {
+            Level::Normal(180)
+        };
+    }
+    let noun = weird.and_then(|w| w.noun());
+    let prefixes = match noun {
+        Some(_) => Vec::new(),
+        None => vec![weird.and_then(|w| w.prefix()).unwrap_or("Wild").into()],
+    };
+    let suffixes = weird
+        .and_then(|w| w.suffix())
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    let evolution = species.tags.iter().find(|tag| {
+        matches!(
+            tag,
+            SpeciesTag::Mega | SpeciesTag::MegaXY | SpeciesTag::Primal
+        )
+    });
+    let mut evolved = false;
+    if let Some(tag) = evolution {
+        if opts.mega_odds.roll(rng) {
+            evolved = true;
+            name = match tag {
+                SpeciesTag::Primal => format!("Primal {}", name),
+                SpeciesTag::MegaXY if rng.gen() => format!("Mega {} X", name),
+                SpeciesTag::MegaXY => format!("Mega {} Y", name),
+                _ => format!("Mega {}", name),
+            };
+        }
+    }
 
-    let mut mon = format!("Wild {}{} (lv{})", name, gender.symbol(), level);
+    let regions: Vec<&str> = species
+        .tags
+        .iter()
+        .filter_map(SpeciesTag::regional_form)
+        .collect();
+    if !evolved && !regions.is_empty() && opts.regional_odds.roll(rng) {
+        if let Some(region) = regions.choose(rng) {
+            name = format!("{} {}", region, name);
+        }
+    }
 
-    if !opts.whitespace {
-        mon = mon.replace(" ","_")
+    let forms: Vec<&Form> = species
+        .forms
+        .iter()
+        .chain(
+            opts.profiles
+                .iter()
+                .flat_map(|profile| profile.forms_for(species_name)),
+        )
+        .collect();
+    if let Ok(form) = forms.choose_weighted(rng, |form| form.weight) {
+        name = form.apply(&name);
     }
-    
+
+    if let Some(disguise) = &species.disguise {
+        if disguise.chance.roll(rng) {
+            let borrowed = match disguise.names.len() {
+                0 => candidates
+                    .choose(rng)
+                    .and_then(|(_, other)| other.names.first()),
+                _ => disguise.names.choose(rng),
+            };
+            if let Some(borrowed) = borrowed {
+                // Disguising as yourself doesn't fool anybody
+                if Some(borrowed) != species.names.first() {
+                    name = disguise.apply(&name, borrowed);
+                }
+            }
+        }
+    }
+
+    if let Some(name_suffix) = weird.and_then(|w| w.name_suffix()) {
+        name.push_str(name_suffix);
+    }
+
+    let mut mon = Wildmon {
+        species: Some(species),
+        index,
+        name_index,
+        name,
+        prefixes,
+        noun,
+        gender,
+        suffixes,
+        level,
+        weird,
+        shiny: false,
+        pokerus: false,
+        whitespace: opts.whitespace,
+        template: opts.template.clone(),
+        monogender_uses_symbol: opts.monogender_uses_symbol,
+    };
+
+    let profile_rules = opts
+        .profiles
+        .iter()
+        .flat_map(|profile| profile.rules_for(species_name));
+    for rule in species.rules.iter().chain(profile_rules) {
+        rule.apply(rng, &mut mon, opts);
+    }
+
+    if opts.shiny_odds.roll(rng) {
+        mon.shiny = true;
+        match (&species.shiny_prefix, weird) {
+            (Some(shiny_prefix), _) => {
+                mon.prefixes = vec![shiny_prefix.clone()];
+                mon.noun = None;
+            }
+            // Contradictory coloration
+            (None, Some(Weird::Pink)) => mon.prefixes = vec!["Wild".into(), "Shiny".into()],
+            _ => mon.prefixes.push("Shiny".into()),
+        }
+    }
+
+    if opts.pokerus_odds.roll(rng) {
+        mon.pokerus = true;
+        mon.suffixes.push(" with Pokérus".into());
+    }
+
     mon
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn parse_species_format() {
         let species_data: &Vec<Species> = &POKEDEX;
 
         assert_eq!(species_data[0].names[0], "Missingno.");
+        assert_eq!(species_data[0].category, Category::Glitch);
+        assert_eq!(species_data[1].category, Category::Canon);
         // Charmander has a defined gender ratio
         assert_eq!(species_data[6].gender, Gender::Ratio(0.125));
         // NidoranF is always female
@@ -145,4 +480,209 @@ mod tests {
         assert_eq!(species_data[151].gender, Gender::Agender);
         assert_eq!(species_data[151].names[0], "Mew");
     }
+
+    #[test]
+    fn shiny_overrides() {
+        let mut rng = StdRng::seed_from_u64(16);
+        let mut opts = WildmonSettings::default();
+        opts.set_shiny_odds(Odds::ALWAYS);
+        opts.set_alt_name_odds(Odds::NEVER);
+
+        let pidgey = wildmon(&mut rng, &POKEDEX[16..17], &opts).to_string();
+        assert!(pidgey.starts_with("True_Shiny_"), "{}", pidgey);
+        let gyarados = wildmon(&mut rng, &POKEDEX[130..131], &opts).to_string();
+        assert!(gyarados.starts_with("Legitimately_Red_"), "{}", gyarados);
+        let bulbasaur = wildmon(&mut rng, &POKEDEX[1..2], &opts).to_string();
+        assert!(bulbasaur.contains("Shiny_Bulbasaur"), "{}", bulbasaur);
+
+        opts.set_shiny_odds(Odds::NEVER);
+        for _ in 0..100 {
+            let mon = wildmon(&mut rng, &POKEDEX, &opts);
+            assert!(!mon.shiny && !mon.to_string().contains("Shiny"), "{}", mon);
+        }
+    }
+
+    #[test]
+    fn mega_evolution_tags() {
+        let mut rng = StdRng::seed_from_u64(382);
+        let mut opts = WildmonSettings::default();
+        opts.set_mega_odds(Odds::ALWAYS);
+        opts.set_alt_name_odds(Odds::NEVER);
+
+        let venusaur = wildmon(&mut rng, &POKEDEX[3..4], &opts).to_string();
+        assert!(venusaur.contains("Mega_Venusaur"), "{}", venusaur);
+        let kyogre = wildmon(&mut rng, &POKEDEX[382..383], &opts).to_string();
+        assert!(kyogre.contains("Primal_Kyogre"), "{}", kyogre);
+        let charizard = wildmon(&mut rng, &POKEDEX[6..7], &opts).to_string();
+        assert!(
+            charizard.contains("Mega_Charizard_X") || charizard.contains("Mega_Charizard_Y"),
+            "{}",
+            charizard
+        );
+        let bulbasaur = wildmon(&mut rng, &POKEDEX[1..2], &opts).to_string();
+        assert!(!bulbasaur.contains("Mega"), "{}", bulbasaur);
+    }
+
+    #[test]
+    fn regional_forms() {
+        let mut rng = StdRng::seed_from_u64(52);
+        let mut opts = WildmonSettings::default();
+        opts.set_regional_odds(Odds::ALWAYS);
+        opts.set_alt_name_odds(Odds::NEVER);
+
+        let vulpix = wildmon(&mut rng, &POKEDEX[37..38], &opts).to_string();
+        assert!(vulpix.contains("Alolan_Vulpix"), "{}", vulpix);
+        let ponyta = wildmon(&mut rng, &POKEDEX[77..78], &opts).to_string();
+        assert!(ponyta.contains("Galarian_Ponyta"), "{}", ponyta);
+        let meowth = wildmon(&mut rng, &POKEDEX[52..53], &opts).to_string();
+        assert!(
+            meowth.contains("Alolan_Meowth") || meowth.contains("Galarian_Meowth"),
+            "{}",
+            meowth
+        );
+
+        opts.set_regional_odds(Odds::NEVER);
+        let vulpix = wildmon(&mut rng, &POKEDEX[37..38], &opts).to_string();
+        assert!(!vulpix.contains("Alolan"), "{}", vulpix);
+    }
+
+    #[test]
+    fn weighted_forms() {
+        let rotom: &Species = &POKEDEX[479];
+        assert_eq!(rotom.forms.len(), 6);
+        assert_eq!(rotom.forms[0].apply("Rotom"), "Rotom");
+        assert_eq!(rotom.forms[1].apply("Rotom"), "Heat Rotom");
+        assert_eq!(POKEDEX[25].forms[0].weight, 18);
+
+        let mut rng = StdRng::seed_from_u64(201);
+        let mut opts = WildmonSettings::default();
+        opts.set_alt_name_odds(Odds::NEVER);
+        for _ in 0..20 {
+            let unown = wildmon(&mut rng, &POKEDEX[201..202], &opts).to_string();
+            assert!(unown.contains("Unown_"), "{}", unown);
+        }
+    }
+
+    #[test]
+    fn alternate_names() {
+        let mut rng = StdRng::seed_from_u64(14);
+        let mut opts = WildmonSettings::default();
+        opts.set_alt_name_odds(Odds::ALWAYS);
+
+        let localized = (0..50)
+            .map(|_| wildmon(&mut rng, &POKEDEX[1..2], &opts).to_string())
+            .filter(|mon| !mon.contains("Bulbasaur"))
+            .count();
+        assert!(localized > 0);
+
+        opts.set_alt_name_odds(Odds::NEVER);
+        for _ in 0..20 {
+            let mon = wildmon(&mut rng, &POKEDEX[1..2], &opts).to_string();
+            assert!(mon.contains("Bulbasaur"), "{}", mon);
+        }
+    }
+
+    #[test]
+    fn disguises() {
+        let mimikyu = POKEDEX[778].disguise.as_ref().unwrap();
+        assert_eq!(mimikyu.chance, Odds(1, 2));
+        assert_eq!(mimikyu.apply("Mimikyu", "Pikachu"), "\"Pikachu\" Mimikyu");
+        let typo = "- names: [Zorua]
+  gender: Agender
+  disguise:
+    template: \"{disguise}!{}\"
+    chanse: [1, 2]
+";
+        assert!(parse_pokedex(typo).is_err());
+
+        // Illusions only borrow from the pokédex being rolled
+        let mut rng = StdRng::seed_from_u64(570);
+        let mut opts = WildmonSettings::default();
+        opts.set_alt_name_odds(Odds::NEVER);
+        opts.set_regional_odds(Odds::NEVER);
+        let zorua_line = &POKEDEX[570..572];
+        for _ in 0..50 {
+            let mon = wildmon(&mut rng, zorua_line, &opts).to_string();
+            assert!(
+                !mon.contains('!')
+                    || mon.contains("Zorua!Zoroark")
+                    || mon.contains("Zoroark!Zorua"),
+                "{}",
+                mon
+            );
+        }
+    }
+
+    #[test]
+    fn glitch_levels() {
+        let mut rng = StdRng::seed_from_u64(180);
+        let mut opts = WildmonSettings::default();
+        opts.set_canon(false);
+        for _ in 0..20 {
+            let mon = wildmon(&mut rng, &POKEDEX[0..1], &opts).to_string();
+            let level = &mon[mon.find("(lv").unwrap() + 3..mon.len() - 1];
+            assert!(level == "180" || level.chars().count() == 1, "{}", mon);
+        }
+    }
+
+    #[test]
+    fn canon_filter() {
+        let mut rng = StdRng::seed_from_u64(899);
+        let mut opts = WildmonSettings::default();
+        assert_eq!(wildmon(&mut rng, &POKEDEX[899..], &opts).species, None);
+
+        opts.set_canon(false);
+        assert!(wildmon(&mut rng, &POKEDEX[899..], &opts).species.is_some());
+    }
+
+    #[test]
+    fn structured_result() {
+        let mut rng = StdRng::seed_from_u64(25);
+        let mut opts = WildmonSettings::default();
+        opts.set_shiny_odds(Odds::ALWAYS);
+        opts.set_alt_name_odds(Odds::ALWAYS);
+
+        let mon = wildmon(&mut rng, &POKEDEX, &opts);
+        let species = mon.species.unwrap();
+        assert_eq!(species, &POKEDEX[mon.index]);
+        assert_eq!(mon.base_name(), species.names[mon.name_index]);
+        assert!(mon.shiny);
+        assert!(mon.prefix().contains("Shiny") || species.shiny_prefix.is_some());
+        assert!(mon.to_string().contains(&mon.name.replace(' ', "_")));
+
+        opts.set_canon(true);
+        let missingno = wildmon(&mut rng, &POKEDEX[899..], &opts);
+        assert_eq!(missingno.species, None);
+        assert_eq!(missingno.base_name(), MISSINGNO);
+    }
+
+    #[test]
+    fn level_range() {
+        let mut rng = StdRng::seed_from_u64(50);
+        let mut opts = WildmonSettings::default();
+        opts.set_level_range(50, 50);
+        opts.set_shiny_odds(Odds::NEVER);
+        opts.set_pokerus_odds(Odds::NEVER);
+
+        for _ in 0..100 {
+            let mon = wildmon(&mut rng, &POKEDEX, &opts).to_string();
+            assert!(
+                mon.contains("(lv50") || mon.contains("Baby") || mon.contains("Omnipotent?"),
+                "{}",
+                mon
+            );
+        }
+    }
+
+    #[test]
+    fn pokerus_comes_last() {
+        let mut rng = StdRng::seed_from_u64(211786);
+        let mut opts = WildmonSettings::default();
+        opts.set_pokerus_odds(Odds::ALWAYS);
+
+        for _ in 0..100 {
+            let mon = wildmon(&mut rng, &POKEDEX, &opts).to_string();
+            assert!(mon.contains("_with_Pokérus_(lv"), "{}", mon);
+        }
+    }
 }
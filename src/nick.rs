@@ -0,0 +1,206 @@
+use std::fmt::{self, Write};
+
+use crate::{template::Piece, Gender, Level, Script, Species, Template, Weird, MISSINGNO};
+
+/// A generated wild Pokémon, which displays as its nick
+#[derive(Clone, Debug, PartialEq)]
+pub struct Wildmon<'a> {
+    /// What was rolled; `None` if the pokédex had nothing to roll, in which
+    /// case the nick is just "Missingno."
+    pub species: Option<&'a Species>,
+    /// Where the species is in the pokédex slice it was rolled from, which
+    /// for a slice of the built-in one isn't its dex number; see `number()`
+    pub index: usize,
+    /// Which of `Species::names` was picked
+    pub name_index: usize,
+    /// The name as displayed, with forms, Mega Evolution and the like applied
+    pub name: String,
+    /// Words before the name, like "Wild" or "Shadow" and "Shiny"
+    pub prefixes: Vec<String>,
+    /// What the Pokémon has been turned into, like a Plushie; this follows
+    /// the name, which then reads as part of the prefix
+    pub noun: Option<&'static str>,
+    pub gender: Gender,
+    /// Text following the gender symbol, like "-ex" and " with Pokérus"
+    pub suffixes: Vec<String>,
+    pub level: Level,
+    pub weird: Option<Weird>,
+    pub shiny: bool,
+    pub pokerus: bool,
+    /// Keep spaces when displayed instead of replacing them with underscores;
+    /// the replacement covers the whole nick, template text and all
+    pub whitespace: bool,
+    /// Layout of the nick when displayed
+    pub template: Template,
+    /// Show the gender symbol even when the species only comes in one gender
+    pub monogender_uses_symbol: bool,
+}
+
+impl<'a> Wildmon<'a> {
+    /// What's left when there was nothing to roll
+    pub(crate) fn missingno(whitespace: bool) -> Wildmon<'a> {
+        Wildmon {
+            species: None,
+            index: 0,
+            name_index: 0,
+            name: MISSINGNO.into(),
+            prefixes: Vec::new(),
+            noun: None,
+            gender: Gender::Agender,
+            suffixes: Vec::new(),
+            level: Level::Normal(0),
+            weird: None,
+            shiny: false,
+            pokerus: false,
+            whitespace,
+            template: Template::default(),
+            monogender_uses_symbol: true,
+        }
+    }
+
+    /// The name picked from `Species::names`, before any decoration
+    pub fn base_name(&self) -> &'a str {
+        self.species
+            .and_then(|species| species.names.get(self.name_index))
+            .map_or(MISSINGNO, String::as_str)
+    }
+
+    /// The species' first name, whichever name was picked
+    pub fn species_name(&self) -> &'a str {
+        self.species
+            .and_then(|species| species.names.first())
+            .map_or(MISSINGNO, String::as_str)
+    }
+
+    /// The species' national dex number, if it has one
+    pub fn number(&self) -> Option<u16> {
+        self.species.and_then(|species| species.number)
+    }
+
+    /// The writing system of the picked name
+    pub fn script(&self) -> Script {
+        Script::of(self.base_name())
+    }
+
+    /// All the prefixes as written in the nick
+    pub fn prefix(&self) -> String {
+        self.prefixes.join(" ")
+    }
+
+    /// The gender symbol, unless it goes without saying
+    pub fn gender_symbol(&self) -> &'static str {
+        let monogender = self
+            .species
+            .is_some_and(|species| matches!(species.gender, Gender::Male | Gender::Female));
+        if monogender && !self.monogender_uses_symbol {
+            return "";
+        }
+        self.gender.symbol()
+    }
+
+    fn render(&self) -> Result<String, fmt::Error> {
+        if self.species.is_none() {
+            return Ok(MISSINGNO.into());
+        }
+        let mut nick = String::new();
+        for piece in self.template.pieces() {
+            match piece {
+                Piece::Text(text) => nick.push_str(text),
+                Piece::Prefix => nick.push_str(&self.prefix()),
+                Piece::Name => {
+                    nick.push_str(&self.name);
+                    if let Some(noun) = self.noun {
+                        write!(nick, " {}", noun)?;
+                    }
+                }
+                Piece::Gender => nick.push_str(self.gender_symbol()),
+                Piece::Level => write!(nick, "{}", self.level)?,
+                Piece::Suffix => nick.extend(self.suffixes.iter().map(String::as_str)),
+                Piece::Dex(width) => {
+                    if let Some(number) = self.number() {
+                        write!(nick, "{:0width$}", number, width = width)?
+                    }
+                }
+            }
+        }
+        // Squash the gaps left by empty placeholders
+        let words: Vec<&str> = nick.split(' ').filter(|word| !word.is_empty()).collect();
+        Ok(words.join(" "))
+    }
+}
+
+impl fmt::Display for Wildmon<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let nick = self.render()?;
+        if self.whitespace {
+            f.write_str(&nick)
+        } else {
+            f.write_str(&nick.replace(' ', "_"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::POKEDEX;
+
+    #[test]
+    fn plushies_restructure_the_nick() {
+        let mon = Wildmon {
+            species: Some(&POKEDEX[25]),
+            index: 25,
+            name_index: 0,
+            name: "Pikachu".into(),
+            prefixes: vec!["Shiny".into()],
+            noun: Weird::Toy.noun(),
+            gender: Gender::Male,
+            suffixes: Vec::new(),
+            level: Level::Normal(12),
+            weird: Some(Weird::Toy),
+            shiny: true,
+            pokerus: false,
+            whitespace: true,
+            template: Template::default(),
+            monogender_uses_symbol: true,
+        };
+        assert_eq!(mon.to_string(), "Shiny Pikachu Toy♂ (lv12)");
+        let mon = Wildmon {
+            prefixes: Vec::new(),
+            noun: Weird::Plushie.noun(),
+            whitespace: false,
+            ..mon
+        };
+        assert_eq!(mon.to_string(), "Pikachu_Plushie♂_(lv12)");
+        assert_eq!(mon.base_name(), "Pikachu");
+    }
+
+    #[test]
+    fn templates() {
+        let mut mon = Wildmon {
+            prefixes: vec!["Wild".into()],
+            suffixes: vec!["☆".into()],
+            ..Wildmon::missingno(true)
+        };
+        mon.species = Some(&POKEDEX[29]);
+        mon.index = 0;
+        mon.name = "Nidoran".into();
+        mon.gender = Gender::Female;
+        mon.level = Level::Normal(12);
+        assert_eq!(mon.to_string(), "Wild Nidoran♀☆ (lv12)");
+
+        mon.template = "{name}{gender} Lv.{level}".parse().unwrap();
+        assert_eq!(mon.to_string(), "Nidoran♀ Lv.12");
+        mon.monogender_uses_symbol = false;
+        assert_eq!(mon.to_string(), "Nidoran Lv.12");
+        // The template's own spaces are only kept with the names'
+        mon.whitespace = false;
+        assert_eq!(mon.to_string(), "Nidoran_Lv.12");
+        mon.whitespace = true;
+
+        mon.template = "#{dex:03} {prefix} {name}".parse().unwrap();
+        mon.prefixes.clear();
+        mon.whitespace = false;
+        assert_eq!(mon.to_string(), "#029_Nidoran");
+    }
+}
@@ -0,0 +1,239 @@
+use std::{error::Error, fmt, io, str::FromStr};
+
+use serde::{Serialize, Serializer};
+use serde_derive::Serialize;
+
+use crate::{Gender, Level, Weird, Wildmon};
+
+/// The writing system a name is in, the closest the pokédex lets us get to
+/// its language
+///
+/// Names aren't labelled in the data, so English, German, French and the fan
+/// translations all come out as `Latin`. Kana is checked before Han, since
+/// Japanese names can have kanji in them too.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Script {
+    Latin,
+    /// Hiragana and katakana, for Japanese
+    Kana,
+    /// For Korean
+    Hangul,
+    /// For Chinese
+    Han,
+    Other,
+}
+
+impl Script {
+    /// Guess from the characters in `name`
+    pub fn of(name: &str) -> Script {
+        let any = |ranges: &[(char, char)]| {
+            name.chars()
+                .any(|c| ranges.iter().any(|&(low, high)| (low..=high).contains(&c)))
+        };
+        if any(&[('\u{3040}', '\u{30ff}'), ('\u{ff66}', '\u{ff9f}')]) {
+            Script::Kana
+        } else if any(&[('\u{1100}', '\u{11ff}'), ('\u{ac00}', '\u{d7af}')]) {
+            Script::Hangul
+        } else if any(&[('\u{4e00}', '\u{9fff}'), ('\u{3400}', '\u{4dbf}')]) {
+            Script::Han
+        } else if name
+            .chars()
+            .filter(|c| c.is_alphabetic())
+            .all(|c| c <= '\u{24f}')
+        {
+            // Basic Latin through Latin Extended-B, accents and all
+            Script::Latin
+        } else {
+            Script::Other
+        }
+    }
+}
+
+/// What a `Wildmon` looks like serialized
+#[derive(Serialize)]
+struct Record<'m> {
+    species: &'m str,
+    /// The national dex number
+    dex: Option<u16>,
+    /// Where the species is in the pokédex slice it was rolled from
+    index: Option<usize>,
+    name: &'m str,
+    /// A guess at the language, from the writing system
+    script: Script,
+    gender: Gender,
+    level: Level,
+    prefixes: &'m [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    noun: Option<&'static str>,
+    suffixes: &'m [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    weird: Option<Weird>,
+    shiny: bool,
+    pokerus: bool,
+    nick: String,
+}
+
+impl Serialize for Wildmon<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Record {
+            species: self.species_name(),
+            dex: self.number(),
+            index: self.species.map(|_| self.index),
+            name: self.base_name(),
+            script: self.script(),
+            gender: self.gender,
+            level: self.level,
+            prefixes: &self.prefixes,
+            noun: self.noun,
+            suffixes: &self.suffixes,
+            weird: self.weird,
+            shiny: self.shiny,
+            pokerus: self.pokerus,
+            nick: self.to_string(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// How to write out generated Pokémon
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Format {
+    /// Just the nicks, one per line
+    Text,
+    /// A JSON array of records
+    Json,
+    /// A YAML sequence of records
+    Yaml,
+    /// One JSON record per line, for streaming
+    Ndjson,
+}
+
+impl FromStr for Format {
+    type Err = UnknownFormat;
+
+    fn from_str(s: &str) -> Result<Format, UnknownFormat> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "yaml" => Ok(Format::Yaml),
+            "ndjson" => Ok(Format::Ndjson),
+            _ => Err(UnknownFormat(s.to_string())),
+        }
+    }
+}
+
+/// A format name `Format` doesn't know
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnknownFormat(pub String);
+
+impl fmt::Display for UnknownFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown output format `{}`", self.0)
+    }
+}
+
+impl Error for UnknownFormat {}
+
+/// Write `mons` to `out` in `format`
+///
+/// Text and NDJSON are written as the Pokémon come; JSON and YAML need them
+/// all before anything is written.
+pub fn write_mons<'a, W, I>(out: &mut W, format: Format, mons: I) -> io::Result<()>
+where
+    W: io::Write + ?Sized,
+    I: IntoIterator<Item = Wildmon<'a>>,
+{
+    match format {
+        Format::Text => {
+            for mon in mons {
+                writeln!(out, "{}", mon)?;
+            }
+        }
+        Format::Ndjson => {
+            for mon in mons {
+                serde_json::to_writer(&mut *out, &mon)?;
+                writeln!(out)?;
+            }
+        }
+        Format::Json => {
+            let mons: Vec<Wildmon> = mons.into_iter().collect();
+            serde_json::to_writer_pretty(&mut *out, &mons)?;
+            writeln!(out)?;
+        }
+        Format::Yaml => {
+            let mons: Vec<Wildmon> = mons.into_iter().collect();
+            // Rendered up front so write errors keep their kind
+            let yaml = serde_yaml::to_string(&mons)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+            write!(out, "{}", yaml)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wildmon, Odds, WildmonSettings, POKEDEX};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn name_scripts() {
+        let scripts: Vec<Script> = POKEDEX[1]
+            .names
+            .iter()
+            .map(|name| Script::of(name))
+            .collect();
+        assert_eq!(
+            scripts,
+            [
+                Script::Latin,
+                Script::Kana,
+                Script::Latin,
+                Script::Latin,
+                Script::Hangul,
+                Script::Han,
+                Script::Han,
+                Script::Latin,
+            ]
+        );
+        assert_eq!(Script::of("Квакуша"), Script::Other);
+    }
+
+    #[test]
+    fn records() {
+        let mut rng = StdRng::seed_from_u64(19);
+        let mut opts = WildmonSettings::default();
+        opts.set_alt_name_odds(Odds::NEVER);
+        opts.set_shiny_odds(Odds::ALWAYS);
+        let mons: Vec<Wildmon> = (0..3)
+            .map(|_| wildmon(&mut rng, &POKEDEX[25..26], &opts))
+            .collect();
+
+        let mut ndjson = Vec::new();
+        write_mons(&mut ndjson, Format::Ndjson, mons.clone()).unwrap();
+        let ndjson = String::from_utf8(ndjson).unwrap();
+        assert_eq!(ndjson.lines().count(), 3);
+        let record: serde_json::Value =
+            serde_json::from_str(ndjson.lines().next().unwrap()).unwrap();
+        assert_eq!(record["species"], "Pikachu");
+        assert_eq!(record["dex"], 25);
+        assert_eq!(record["index"], 0);
+        assert_eq!(record["script"], "Latin");
+        assert_eq!(record["shiny"], true);
+        assert!(record["nick"].as_str().unwrap().contains("Pikachu"));
+
+        let mut yaml = Vec::new();
+        write_mons(&mut yaml, Format::Yaml, mons.clone()).unwrap();
+        let records: Vec<serde_yaml::Value> = serde_yaml::from_slice(&yaml).unwrap();
+        assert_eq!(records.len(), 3);
+
+        let mut json = Vec::new();
+        write_mons(&mut json, Format::Json, mons).unwrap();
+        let records: Vec<serde_json::Value> = serde_json::from_slice(&json).unwrap();
+        assert_eq!(records[2]["species"], "Pikachu");
+
+        assert_eq!("ndjson".parse(), Ok(Format::Ndjson));
+        assert!("xml".parse::<Format>().is_err());
+    }
+}
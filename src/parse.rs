@@ -0,0 +1,392 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    weird::WEIRD_DIE, Disguise, Gender, Level, Species, Weird, Wildmon, WildmonSettings, MISSINGNO,
+};
+
+/// Why a nick couldn't be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseNickError {
+    /// There's no "(lv…)" at the end
+    MissingLevel,
+    /// The level isn't one `Level` could have displayed
+    BadLevel(String),
+    /// No name from the pokédex, in any language, appears in the nick
+    UnknownSpecies(String),
+}
+
+impl fmt::Display for ParseNickError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseNickError::MissingLevel => write!(f, "no level at the end of the nick"),
+            ParseNickError::BadLevel(level) => write!(f, "unrecognized level `{}`", level),
+            ParseNickError::UnknownSpecies(name) => write!(f, "no species found in `{}`", name),
+        }
+    }
+}
+
+impl Error for ParseNickError {}
+
+/// Always the last suffix, when there is one
+static POKERUS: &str = " with Pokérus";
+
+/// Read a nick made by `wildmon()` back into a `Wildmon`
+///
+/// Only nicks in the default layout can be read, though with their spaces
+/// replaced by underscores is fine too; the result displays with the
+/// template from `opts`. The species is found by looking for the longest
+/// name, in any language, from `pokedex`; the prefixes are whatever the
+/// generator could have put in front of it, including rules from the
+/// profiles in `opts`, and the rest of the text up to the gender symbol is
+/// taken to be the decorated name. Which weird roll, if any, made the nick is
+/// worked out from the text, so it can only be as good as what the nick kept
+/// of it.
+pub fn parse_nick<'a>(
+    nick: &str,
+    pokedex: &'a [Species],
+    opts: &WildmonSettings,
+) -> Result<Wildmon<'a>, ParseNickError> {
+    // Nicks with their spaces kept still have the one before the level, and
+    // underscores in them are part of a name, like "Reggie_Giygas"
+    let whitespace = nick.contains(' ') || !nick.contains('_');
+    let nick = match whitespace {
+        true => nick.to_string(),
+        false => nick.replace('_', " "),
+    };
+    if nick == MISSINGNO {
+        return Ok(Wildmon::missingno(whitespace));
+    }
+
+    let rest = nick.strip_suffix(')').ok_or(ParseNickError::MissingLevel)?;
+    let level_at = rest.rfind(" (lv").ok_or(ParseNickError::MissingLevel)?;
+    let level_text = &rest[level_at + " (lv".len()..];
+    let mut rest = &rest[..level_at];
+
+    let mut suffixes = Vec::new();
+    let pokerus = match rest.strip_suffix(POKERUS) {
+        Some(stripped) => {
+            rest = stripped;
+            true
+        }
+        None => false,
+    };
+    let mut weird = None;
+    for candidate in all_weird() {
+        if let Some(stripped) = candidate.suffix().and_then(|s| rest.strip_suffix(s)) {
+            rest = stripped;
+            suffixes.push(candidate.suffix().unwrap_or_default().to_string());
+            weird = Some(candidate);
+            break;
+        }
+    }
+    if pokerus {
+        suffixes.push(POKERUS.to_string());
+    }
+
+    let gender = [Gender::Male, Gender::Female]
+        .iter()
+        .copied()
+        .find(|gender| rest.ends_with(gender.symbol()))
+        .unwrap_or(Gender::Agender);
+    rest = &rest[..rest.len() - gender.symbol().len()];
+
+    let mut noun = None;
+    for candidate in all_weird() {
+        if let Some(stripped) = candidate.noun().and_then(|n| rest.strip_suffix(n)) {
+            if let Some(stripped) = stripped.strip_suffix(' ') {
+                rest = stripped;
+                noun = candidate.noun();
+                weird = Some(candidate);
+                break;
+            }
+        }
+    }
+
+    let (index, name_index) = find_species(rest, gender, whitespace, pokedex, opts)
+        .ok_or_else(|| ParseNickError::UnknownSpecies(rest.to_string()))?;
+    let species = &pokedex[index];
+
+    let known = known_prefixes(species, opts);
+    let mut prefixes = Vec::new();
+    let mut name = rest;
+    while let Some(prefix) = known
+        .iter()
+        .filter(|prefix| {
+            name.strip_prefix(prefix.as_str())
+                .is_some_and(|after| after.starts_with(' '))
+        })
+        .max_by_key(|prefix| prefix.len())
+    {
+        prefixes.push(prefix.clone());
+        name = &name[prefix.len() + 1..];
+    }
+    let mut name = name.to_string();
+    if let Some(base) = species.names.get(name_index).filter(|_| !whitespace) {
+        name = name.replacen(&base.replace('_', " "), base, 1);
+    }
+
+    for prefix in &prefixes {
+        if let Some(candidate) = all_weird().find(|w| w.prefix() == Some(prefix.as_str())) {
+            weird = Some(candidate);
+        }
+    }
+    if name.ends_with(Weird::Delta.name_suffix().unwrap_or_default()) {
+        weird = weird.or(Some(Weird::Delta));
+    }
+    // Pidgey's "Shiny" is only a joke when it has a shiny prefix of its own
+    let shiny_prefix = species.shiny_prefix.as_deref().unwrap_or("Shiny");
+    let shiny = prefixes.iter().any(|prefix| prefix == shiny_prefix);
+    let is_missingno = species.names.first().is_some_and(|n| n == MISSINGNO);
+
+    Ok(Wildmon {
+        species: Some(species),
+        index,
+        name_index,
+        name,
+        prefixes,
+        noun,
+        gender,
+        suffixes,
+        level: parse_level(level_text, is_missingno)?,
+        weird,
+        shiny,
+        pokerus,
+        whitespace,
+        template: opts.template.clone(),
+        monogender_uses_symbol: opts.monogender_uses_symbol,
+    })
+}
+
+/// Every modifier on the weird die; Star comes up twice, which does no harm
+fn all_weird() -> impl Iterator<Item = Weird> {
+    (1..=WEIRD_DIE).filter_map(Weird::from_roll)
+}
+
+/// Everything that can come before the name for this species
+fn known_prefixes(species: &Species, opts: &WildmonSettings) -> Vec<String> {
+    let species_name = species.names.first().map_or(MISSINGNO, String::as_str);
+    let profile_rules = opts
+        .profiles
+        .iter()
+        .flat_map(|profile| profile.rules_for(species_name));
+    let mut known: Vec<String> = vec!["Wild".into(), "Shiny".into()];
+    known.extend(all_weird().filter_map(|w| w.prefix()).map(String::from));
+    known.extend(species.shiny_prefix.iter().cloned());
+    known.extend(
+        species
+            .rules
+            .iter()
+            .chain(profile_rules)
+            .filter_map(|rule| rule.prefix.clone()),
+    );
+    known
+}
+
+/// Whether the gender fits, whether it's in its disguise's template, whether it
+/// has a disguise, name length, position
+type Score = (bool, bool, bool, usize, usize);
+
+/// The pokédex index and name index of the best name found in `text`
+///
+/// Besides its names, a species is known by any form or rule that renames it
+/// outright, like Shaymin's "Skymin". A name has to stand on its own, not as
+/// part of a longer word, though it can be followed by a Delta sign; without
+/// `whitespace`, underscores in names are looked for as spaces. Species that
+/// can have `gender` win, which is what tells the two Nidoran apart; then
+/// names sitting where their species' disguise template puts them, names of
+/// species with a disguise over the names they borrow, longer names over
+/// shorter ones, and finally later ones over earlier, as the real name tends
+/// to come last.
+fn find_species(
+    text: &str,
+    gender: Gender,
+    whitespace: bool,
+    pokedex: &[Species],
+    opts: &WildmonSettings,
+) -> Option<(usize, usize)> {
+    let delta = Weird::Delta.name_suffix().unwrap_or_default();
+    let mut best: Option<(Score, (usize, usize))> = None;
+    for (dex, species) in pokedex.iter().enumerate() {
+        let possible = match (species.gender, gender) {
+            (Gender::Ratio(_), Gender::Male) | (Gender::Ratio(_), Gender::Female) => true,
+            (species_gender, gender) => species_gender == gender,
+        };
+        let species_name = species.names.first().map_or(MISSINGNO, String::as_str);
+        let profile_rules = opts
+            .profiles
+            .iter()
+            .flat_map(|profile| profile.rules_for(species_name));
+        let renames = species
+            .forms
+            .iter()
+            .map(|form| &form.template)
+            .chain(
+                species
+                    .rules
+                    .iter()
+                    .chain(profile_rules)
+                    .flat_map(|rule| &rule.name),
+            )
+            .filter(|template| !template.contains("{}"))
+            .map(|template| (0, template));
+        for (name_index, name) in species.names.iter().enumerate().chain(renames) {
+            if name.is_empty() {
+                continue;
+            }
+            let name = match whitespace {
+                true => name.to_string(),
+                false => name.replace('_', " "),
+            };
+            for (at, _) in text.match_indices(name.as_str()) {
+                let before = text[..at].chars().next_back();
+                let after = &text[at + name.len()..];
+                if before.is_some_and(char::is_alphanumeric) {
+                    continue;
+                }
+                if !after.starts_with(delta)
+                    && after.chars().next().is_some_and(char::is_alphanumeric)
+                {
+                    continue;
+                }
+                let disguised = species
+                    .disguise
+                    .as_ref()
+                    .is_some_and(|disguise| in_template(disguise, &text[..at], after));
+                let score = (
+                    possible,
+                    disguised,
+                    species.disguise.is_some(),
+                    name.chars().count(),
+                    at,
+                );
+                if best.as_ref().is_none_or(|(best, _)| score > *best) {
+                    best = Some((score, (dex, name_index)));
+                }
+            }
+        }
+    }
+    best.map(|(_, found)| found)
+}
+
+/// Whether `before` and `after` could surround a name `disguise` was applied
+/// to, with a name borrowed on the side the template has it
+fn in_template(disguise: &Disguise, before: &str, after: &str) -> bool {
+    let (pre, post) = match disguise.template.split_once("{}") {
+        Some(parts) => parts,
+        None => return false,
+    };
+    // Only the text between the borrowed name and the real one is known
+    match (pre.rsplit_once("{disguise}"), post.split_once("{disguise}")) {
+        (Some((_, pre)), _) => {
+            before.len() > pre.len() && before.ends_with(pre) && after.starts_with(post)
+        }
+        (None, Some((post, _))) => {
+            after.len() > post.len() && after.starts_with(post) && before.ends_with(pre)
+        }
+        (None, None) => false,
+    }
+}
+
+fn parse_level(text: &str, glitch: bool) -> Result<Level, ParseNickError> {
+    let bad = || ParseNickError::BadLevel(text.to_string());
+    let number = |n: &str| n.parse::<u8>().map_err(|_| bad());
+
+    let mut chars = text.chars();
+    if let (true, Some(c), None) = (glitch, chars.next(), chars.next()) {
+        if (' '..='0').contains(&c) {
+            return Ok(Level::Glitch(c));
+        }
+    }
+    if text == Level::Infinite.to_string() {
+        return Ok(Level::Infinite);
+    }
+    match text.strip_suffix('i') {
+        Some(imaginary) => match imaginary.split_once('+') {
+            Some((real, imaginary)) => Ok(Level::Complex(number(real)?, number(imaginary)?)),
+            None => Ok(Level::Imaginary(number(imaginary)?)),
+        },
+        None => Ok(Level::Normal(number(text)?)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wildmon, Profile, POKEDEX};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn parse_examples() {
+        let opts = WildmonSettings::default();
+        let mon = parse_nick("Wild_Bulbasaur♂_(lv15)", &POKEDEX, &opts).unwrap();
+        assert_eq!(mon.index, 1);
+        assert_eq!(mon.prefixes, ["Wild"]);
+        assert_eq!(mon.gender, Gender::Male);
+        assert_eq!(mon.level, Level::Normal(15));
+        assert!(!mon.whitespace);
+
+        let mon = parse_nick("Top Percentage Rattata♀ (lv98)", &POKEDEX, &opts).unwrap();
+        assert_eq!(mon.index, 19);
+        assert_eq!(mon.prefixes, ["Top Percentage"]);
+
+        let mon = parse_nick("Shadow Shiny Nidoran♀ (lv5)", &POKEDEX, &opts).unwrap();
+        assert_eq!((mon.index, mon.gender), (29, Gender::Female));
+        assert_eq!(mon.weird, Some(Weird::Shadow));
+        assert!(mon.shiny);
+        let mon = parse_nick("Wild ニドラン♂ (lv5)", &POKEDEX, &opts).unwrap();
+        assert_eq!((mon.index, mon.gender), (32, Gender::Male));
+
+        let mon = parse_nick(
+            "Wild_Pikachu!Zorua♀-ex_with_Pokérus_(lv3+7i)",
+            &POKEDEX,
+            &opts,
+        );
+        let mon = mon.unwrap();
+        assert_eq!((mon.index, mon.name.as_str()), (570, "Pikachu!Zorua"));
+        assert_eq!(mon.suffixes, ["-ex", " with Pokérus"]);
+        assert_eq!(mon.level, Level::Complex(3, 7));
+        assert!(mon.pokerus);
+
+        // A disguise's template tells the real name from the borrowed one
+        let mon = parse_nick("Mimikyu!索罗亚_Toy♂_(lv35)", &POKEDEX, &opts).unwrap();
+        assert_eq!((mon.index, mon.name.as_str()), (570, "Mimikyu!索罗亚"));
+
+        // Underscores can be part of a name, with or without spaces kept
+        for nick in &["Wild_Reggie_Giygas_(lv83)", "Wild Reggie_Giygas (lv62)"] {
+            let mon = parse_nick(nick, &POKEDEX, &opts).unwrap();
+            assert_eq!((mon.index, mon.name.as_str()), (486, "Reggie_Giygas"));
+            assert_eq!(mon.to_string(), *nick);
+        }
+
+        assert_eq!(
+            parse_nick("Wild_Bulbasaur♂", &POKEDEX, &opts),
+            Err(ParseNickError::MissingLevel)
+        );
+        assert_eq!(
+            parse_nick("Wild Bulba♂ (lv1)", &POKEDEX, &opts),
+            Err(ParseNickError::UnknownSpecies("Wild Bulba".into()))
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut rng = StdRng::seed_from_u64(18);
+        let mut opts = WildmonSettings::default();
+        opts.set_canon(false);
+        opts.add_profile(Profile::builtin("rp").unwrap());
+        for whitespace in &[false, true] {
+            opts.set_whitespace(*whitespace);
+            for _ in 0..500 {
+                let mon = wildmon(&mut rng, &POKEDEX, &opts);
+                let nick = mon.to_string();
+                let parsed = parse_nick(&nick, &POKEDEX, &opts).unwrap();
+                assert_eq!(parsed.to_string(), nick);
+                assert_eq!(parsed.index, mon.index, "{}", nick);
+                assert_eq!(parsed.gender, mon.gender, "{}", nick);
+                assert_eq!(parsed.level, mon.level, "{}", nick);
+                assert_eq!(parsed.prefixes, mon.prefixes, "{}", nick);
+                assert_eq!((parsed.shiny, parsed.pokerus), (mon.shiny, mon.pokerus));
+            }
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{Algorithm, Form, Rule};
+
+/// A named set of extra rules and forms, keyed by the species' first name
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    pub name: String,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub rules: BTreeMap<String, Vec<Rule>>,
+
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    #[serde(default)]
+    pub forms: BTreeMap<String, Vec<Form>>,
+}
+
+impl Profile {
+    /// Load a profile shipped with the crate, like "rp", as of
+    /// `Algorithm::LATEST`; see `Algorithm::profile()` for older versions
+    pub fn builtin(name: &str) -> Option<Profile> {
+        Algorithm::LATEST.profile(name)
+    }
+
+    pub fn from_yaml(yaml: &str) -> Result<Profile, serde_yaml::Error> {
+        serde_yaml::from_str(yaml)
+    }
+
+    pub fn rules_for(&self, name: &str) -> &[Rule] {
+        self.rules.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn forms_for(&self, name: &str) -> &[Form] {
+        self.forms.get(name).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_profiles() {
+        let rp = Profile::builtin("rp").unwrap();
+        assert_eq!(rp.forms_for("Rotom")[0].apply("Rotom"), "Rotom-Z");
+        assert_eq!(rp.rules_for("Cofagrigus").len(), 1);
+        assert!(rp.rules_for("Bulbasaur").is_empty());
+
+        assert!(Profile::builtin("classic").is_some());
+        assert!(Profile::builtin("family-friendly").is_some());
+        assert!(Profile::builtin("nonexistent").is_none());
+    }
+}
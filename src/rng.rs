@@ -0,0 +1,41 @@
+use rand::SeedableRng;
+use rand_chacha::ChaCha20Rng;
+
+/// The RNG behind `seeded_rng()`
+///
+/// This is ChaCha20 as implemented by `rand_chacha` 0.3, which, unlike
+/// `rand`'s `StdRng`, promises the same stream on every platform and in every
+/// release. Changing it would change which nick a seed gives, so it will only
+/// happen in a release that breaks compatibility.
+pub type WildmonRng = ChaCha20Rng;
+
+/// A portable RNG for reproducing nicks from a seed
+///
+/// The seed is expanded into a ChaCha20 key with `SeedableRng::seed_from_u64`,
+/// so the same seed gives the same nicks wherever the same release of this
+/// crate runs `wildmon()` with the same pokédex and settings.
+pub fn seeded_rng(seed: u64) -> WildmonRng {
+    WildmonRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{wildmon, WildmonSettings, POKEDEX};
+    use rand::RngCore;
+
+    #[test]
+    fn seeds_are_stable() {
+        assert_eq!(seeded_rng(42).next_u64(), 9482535800248027256);
+
+        let opts = WildmonSettings::default();
+        let nicks = |seed| -> Vec<String> {
+            let mut rng = seeded_rng(seed);
+            (0..20)
+                .map(|_| wildmon(&mut rng, &POKEDEX, &opts).to_string())
+                .collect()
+        };
+        assert_eq!(nicks(42), nicks(42));
+        assert_ne!(nicks(42), nicks(43));
+    }
+}
@@ -0,0 +1,189 @@
+use rand::Rng;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{Gender, Odds, Wildmon, WildmonSettings};
+
+/// A species-specific joke, applied when its conditions all hold
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    #[serde(default)]
+    pub when: Condition,
+
+    /// Replaces the prefix outright
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// Template applied to the name, as with `Form`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+/// Conditions on a Pokémon in progress; unset ones always hold
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Condition {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub min_level: Option<u8>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub max_level: Option<u8>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub gender: Option<Gender>,
+
+    /// The prefix so far must be exactly this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// The prefix so far must be anything but this
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub not_prefix: Option<String>,
+
+    /// A profile with this name must be active
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// No profile with this name may be active
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub not_profile: Option<String>,
+
+    /// Only rolled once every other condition holds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub chance: Option<Odds>,
+}
+
+impl Condition {
+    /// Whether `min_level` and `max_level` hold for a level's real part
+    pub(crate) fn level_holds(&self, level: Option<u8>) -> bool {
+        if let Some(min_level) = self.min_level {
+            if !matches!(level, Some(level) if level >= min_level) {
+                return false;
+            }
+        }
+        if let Some(max_level) = self.max_level {
+            if !matches!(level, Some(level) if level <= max_level) {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub(crate) fn holds<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mon: &Wildmon,
+        opts: &WildmonSettings,
+    ) -> bool {
+        if !self.level_holds(mon.level.number()) {
+            return false;
+        }
+        if self.gender.is_some_and(|gender| gender != mon.gender) {
+            return false;
+        }
+        if self.prefix.as_ref().is_some_and(|p| *p != mon.prefix()) {
+            return false;
+        }
+        if self.not_prefix.as_ref().is_some_and(|p| *p == mon.prefix()) {
+            return false;
+        }
+        if self.profile.as_ref().is_some_and(|p| !opts.has_profile(p)) {
+            return false;
+        }
+        if self
+            .not_profile
+            .as_ref()
+            .is_some_and(|p| opts.has_profile(p))
+        {
+            return false;
+        }
+        self.chance.is_none_or(|chance| chance.roll(rng))
+    }
+}
+
+impl Rule {
+    pub(crate) fn apply<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        mon: &mut Wildmon,
+        opts: &WildmonSettings,
+    ) {
+        if !self.when.holds(rng, mon, opts) {
+            return;
+        }
+        if let Some(prefix) = &self.prefix {
+            mon.prefixes = vec![prefix.clone()];
+            // A new prefix leaves nothing for the Pokémon to be turned into
+            mon.noun = None;
+        }
+        if let Some(template) = &self.name {
+            mon.name = template.replace("{}", &mon.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Level, Profile, Template, POKEDEX};
+    use rand::thread_rng;
+
+    fn wild(dex: usize, gender: Gender, level: u8) -> Wildmon<'static> {
+        Wildmon {
+            species: Some(&POKEDEX[dex]),
+            index: dex,
+            name_index: 0,
+            name: POKEDEX[dex].names[0].clone(),
+            prefixes: vec!["Wild".into()],
+            noun: None,
+            gender,
+            suffixes: Vec::new(),
+            level: Level::Normal(level),
+            weird: None,
+            shiny: false,
+            pokerus: false,
+            whitespace: true,
+            template: Template::default(),
+            monogender_uses_symbol: true,
+        }
+    }
+
+    #[test]
+    fn percentage_rattata() {
+        let rattata = &POKEDEX[19];
+        let mut mon = wild(19, Gender::Female, 98);
+        let opts = WildmonSettings::default();
+        for rule in &rattata.rules {
+            rule.apply(&mut thread_rng(), &mut mon, &opts);
+        }
+        assert_eq!(mon.to_string(), "Top Percentage Rattata♀ (lv98)");
+
+        let mut mon = wild(19, Gender::Female, 50);
+        for rule in &rattata.rules {
+            rule.apply(&mut thread_rng(), &mut mon, &opts);
+        }
+        assert_eq!(mon.prefix(), "Wild");
+    }
+
+    #[test]
+    fn profile_conditions() {
+        let rule = &POKEDEX[183].rules[0];
+        let mut mon = wild(183, Gender::Male, 30);
+        let mut opts = WildmonSettings::default();
+        opts.add_profile(Profile::builtin("family-friendly").unwrap());
+        for _ in 0..20 {
+            rule.apply(&mut thread_rng(), &mut mon, &opts);
+        }
+        assert_eq!(mon.name, "Marill");
+    }
+}
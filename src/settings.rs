@@ -0,0 +1,319 @@
+use std::ops::RangeInclusive;
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{Algorithm, Gender, Odds, Profile, Template};
+
+/// Options for `wildmon()`
+///
+/// Missing keys take their defaults when deserializing, and unknown keys are
+/// rejected. Profiles may be given by the name of a built-in profile.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct WildmonSettings {
+    /// Which version of the generator to run; pin this to keep nicks from
+    /// stored seeds
+    pub(crate) algorithm: Algorithm,
+    /// Only roll `Category::Canon` species
+    pub(crate) canon: bool,
+    /// Keep spaces instead of replacing them with underscores
+    pub(crate) whitespace: bool,
+    /// Genders to pick from; any gender when empty
+    pub(crate) allow_genders: Vec<Gender>,
+    /// Lowest level rolled, Baby Pokémon and glitches notwithstanding
+    pub(crate) min_level: u8,
+    pub(crate) max_level: u8,
+    pub(crate) shiny_odds: Odds,
+    pub(crate) pokerus_odds: Odds,
+    pub(crate) mega_odds: Odds,
+    pub(crate) regional_odds: Odds,
+    pub(crate) alt_name_odds: Odds,
+    #[serde(with = "profile_list")]
+    pub(crate) profiles: Vec<Profile>,
+    pub(crate) template: Template,
+    /// Show ♂ or ♀ even for species that only come in that gender
+    pub(crate) monogender_uses_symbol: bool,
+}
+
+impl Default for WildmonSettings {
+    fn default() -> Self {
+        WildmonSettings {
+            algorithm: Algorithm::LATEST,
+            canon: true,
+            whitespace: false,
+            allow_genders: Vec::new(),
+            min_level: 1,
+            max_level: 100,
+            shiny_odds: Odds(1, 4096),
+            pokerus_odds: Odds(10, 211786),
+            mega_odds: Odds(2, 10),
+            regional_odds: Odds(1, 10),
+            alt_name_odds: Odds(1, 14),
+            profiles: Vec::new(),
+            template: Template::default(),
+            monogender_uses_symbol: true,
+        }
+    }
+}
+
+impl WildmonSettings {
+    pub fn builder() -> WildmonSettingsBuilder {
+        WildmonSettingsBuilder::default()
+    }
+
+    pub fn set_algorithm(&mut self, algorithm: Algorithm) {
+        self.algorithm = algorithm;
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn set_canon(&mut self, canon: bool) {
+        self.canon = canon;
+    }
+
+    pub fn set_whitespace(&mut self, whitespace: bool) {
+        self.whitespace = whitespace;
+    }
+
+    pub fn allow_gender(&mut self, gender: Gender) {
+        self.allow_genders.push(gender);
+    }
+
+    /// Replace the allowed genders; an empty list allows any of them
+    pub fn set_allowed_genders(&mut self, genders: Vec<Gender>) {
+        self.allow_genders = genders;
+    }
+
+    /// Limit rolled levels to `min..=max`; the bounds are swapped if needed,
+    /// and levels start at 1, so a 0 counts as 1
+    pub fn set_level_range(&mut self, min: u8, max: u8) {
+        self.min_level = min.min(max).max(1);
+        self.max_level = max.max(min).max(1);
+    }
+
+    pub fn level_range(&self) -> RangeInclusive<u8> {
+        let min = self.min_level.min(self.max_level).max(1);
+        let max = self.max_level.max(self.min_level).max(1);
+        min..=max
+    }
+
+    pub fn set_shiny_odds(&mut self, odds: Odds) {
+        self.shiny_odds = odds;
+    }
+
+    pub fn set_pokerus_odds(&mut self, odds: Odds) {
+        self.pokerus_odds = odds;
+    }
+
+    pub fn set_mega_odds(&mut self, odds: Odds) {
+        self.mega_odds = odds;
+    }
+
+    pub fn set_regional_odds(&mut self, odds: Odds) {
+        self.regional_odds = odds;
+    }
+
+    /// Chance of using a random entry of `Species::names` (which may still
+    /// turn out to be the first one) instead of the first
+    pub fn set_alt_name_odds(&mut self, odds: Odds) {
+        self.alt_name_odds = odds;
+    }
+
+    /// Turn on a profile's extra rules and forms, and any rules in the
+    /// pokédex that check for it by name
+    pub fn add_profile(&mut self, profile: Profile) {
+        self.profiles.push(profile);
+    }
+
+    pub fn set_profiles(&mut self, profiles: Vec<Profile>) {
+        self.profiles = profiles;
+    }
+
+    pub fn has_profile(&self, name: &str) -> bool {
+        self.profiles.iter().any(|profile| profile.name == name)
+    }
+
+    /// Lay nicks out differently; see `Template` for the placeholders
+    pub fn set_template(&mut self, template: Template) {
+        self.template = template;
+    }
+
+    pub fn set_monogender_uses_symbol(&mut self, uses_symbol: bool) {
+        self.monogender_uses_symbol = uses_symbol;
+    }
+}
+
+/// Builds `WildmonSettings` from the defaults up
+#[derive(Debug, Clone, Default)]
+pub struct WildmonSettingsBuilder {
+    settings: WildmonSettings,
+}
+
+impl WildmonSettingsBuilder {
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.settings.algorithm = algorithm;
+        self
+    }
+
+    pub fn canon(mut self, canon: bool) -> Self {
+        self.settings.canon = canon;
+        self
+    }
+
+    pub fn whitespace(mut self, whitespace: bool) -> Self {
+        self.settings.whitespace = whitespace;
+        self
+    }
+
+    /// May be called more than once to allow several genders
+    pub fn allow_gender(mut self, gender: Gender) -> Self {
+        self.settings.allow_genders.push(gender);
+        self
+    }
+
+    pub fn level_range(mut self, min: u8, max: u8) -> Self {
+        self.settings.set_level_range(min, max);
+        self
+    }
+
+    pub fn shiny_odds(mut self, odds: Odds) -> Self {
+        self.settings.shiny_odds = odds;
+        self
+    }
+
+    pub fn pokerus_odds(mut self, odds: Odds) -> Self {
+        self.settings.pokerus_odds = odds;
+        self
+    }
+
+    pub fn mega_odds(mut self, odds: Odds) -> Self {
+        self.settings.mega_odds = odds;
+        self
+    }
+
+    pub fn regional_odds(mut self, odds: Odds) -> Self {
+        self.settings.regional_odds = odds;
+        self
+    }
+
+    pub fn alt_name_odds(mut self, odds: Odds) -> Self {
+        self.settings.alt_name_odds = odds;
+        self
+    }
+
+    /// May be called more than once to stack profiles
+    pub fn profile(mut self, profile: Profile) -> Self {
+        self.settings.profiles.push(profile);
+        self
+    }
+
+    pub fn template(mut self, template: Template) -> Self {
+        self.settings.template = template;
+        self
+    }
+
+    pub fn monogender_uses_symbol(mut self, uses_symbol: bool) -> Self {
+        self.settings.monogender_uses_symbol = uses_symbol;
+        self
+    }
+
+    pub fn build(self) -> WildmonSettings {
+        self.settings
+    }
+}
+
+/// Profiles are written as just their name when they match a built-in one
+mod profile_list {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use serde_derive::{Deserialize, Serialize};
+
+    use crate::Profile;
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum Entry<P> {
+        Builtin(String),
+        Custom(P),
+    }
+
+    pub fn serialize<S: Serializer>(
+        profiles: &[Profile],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let entries: Vec<Entry<&Profile>> = profiles
+            .iter()
+            .map(|profile| match Profile::builtin(&profile.name) {
+                Some(builtin) if builtin == *profile => Entry::Builtin(profile.name.clone()),
+                _ => Entry::Custom(profile),
+            })
+            .collect();
+        entries.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Profile>, D::Error> {
+        Vec::<Entry<Profile>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|entry| match entry {
+                Entry::Builtin(name) => Profile::builtin(&name).ok_or_else(|| {
+                    D::Error::custom(format!("unknown built-in profile `{}`", name))
+                }),
+                Entry::Custom(profile) => Ok(profile),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_and_serde() {
+        let settings = WildmonSettings::builder()
+            .canon(false)
+            .whitespace(true)
+            .allow_gender(Gender::Female)
+            .shiny_odds(Odds(1, 8192))
+            .profile(Profile::builtin("rp").unwrap())
+            .template("#{dex:03} {name}".parse().unwrap())
+            .build();
+        assert!(settings.has_profile("rp"));
+
+        let yaml = serde_yaml::to_string(&settings).unwrap();
+        assert!(yaml.contains("- rp"), "{}", yaml);
+        assert!(yaml.contains("template: \"#{dex:03} {name}\""), "{}", yaml);
+        let parsed: WildmonSettings = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(parsed, settings);
+
+        let zero: WildmonSettings = serde_yaml::from_str("min_level: 0").unwrap();
+        assert_eq!(zero.level_range(), 1..=100);
+
+        let partial: WildmonSettings = serde_yaml::from_str("whitespace: true").unwrap();
+        assert!(partial.whitespace);
+        assert!(partial.canon);
+
+        let err = serde_yaml::from_str::<WildmonSettings>("template: \"{nick}\"").unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder"), "{}", err);
+    }
+
+    #[test]
+    fn unknown_keys_are_rejected() {
+        let err = serde_yaml::from_str::<WildmonSettings>("shiny_chance: [1, 2]").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown field `shiny_chance`"),
+            "{}",
+            err
+        );
+
+        let err = serde_yaml::from_str::<WildmonSettings>("profiles: [pr]").unwrap_err();
+        assert!(
+            err.to_string().contains("unknown built-in profile `pr`"),
+            "{}",
+            err
+        );
+    }
+}
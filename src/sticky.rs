@@ -0,0 +1,176 @@
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use hmac::{Hmac, Mac};
+use rand::SeedableRng;
+use sha2::Sha256;
+
+use crate::{wildmon, Species, Wildmon, WildmonRng, WildmonSettings};
+
+const DAY: u64 = 24 * 60 * 60;
+
+/// How often sticky nicks change
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Never,
+    /// At midnight UTC
+    Daily,
+    /// At midnight UTC going into Monday
+    Weekly,
+    /// Every so often, counted from the Unix epoch; periods shorter than a
+    /// second count as a second
+    Every(Duration),
+}
+
+impl Rotation {
+    /// Which period `now` falls in, along with the period's length in seconds
+    fn period(&self, now: SystemTime) -> (u64, u64) {
+        let secs = now
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs());
+        match self {
+            Rotation::Never => (0, 0),
+            Rotation::Daily => (secs / DAY, DAY),
+            // The epoch was a Thursday
+            Rotation::Weekly => ((secs + 3 * DAY) / (7 * DAY), 7 * DAY),
+            Rotation::Every(length) => {
+                let length = length.as_secs().max(1);
+                (secs / length, length)
+            }
+        }
+    }
+}
+
+/// Nicks that stay the same for the same identity, without storing anything
+///
+/// The identity (a JID, a hashed IP address, a session ID...) and the current
+/// rotation period are run through HMAC-SHA256 keyed with a server secret,
+/// and the result seeds a `WildmonRng` for `wildmon()`. Without the secret,
+/// nobody can work out whose nick is whose. Nicks only stay put while the
+/// pokédex and settings do, so pin the settings' `Algorithm` and roll from
+/// its `pokedex()`.
+#[derive(Clone)]
+pub struct StickyNicks {
+    secret: Vec<u8>,
+    rotation: Rotation,
+}
+
+impl StickyNicks {
+    /// Nicks keyed by `secret` that never rotate
+    pub fn new<S: Into<Vec<u8>>>(secret: S) -> StickyNicks {
+        StickyNicks {
+            secret: secret.into(),
+            rotation: Rotation::Never,
+        }
+    }
+
+    pub fn rotation(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// The RNG `identity` gets for the rotation period `now` falls in
+    pub fn rng_at(&self, identity: &str, now: SystemTime) -> WildmonRng {
+        let (period, length) = self.rotation.period(now);
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(&self.secret).expect("HMAC takes keys of any length");
+        mac.update(b"wildmon sticky nick\0");
+        mac.update(&(identity.len() as u64).to_le_bytes());
+        mac.update(identity.as_bytes());
+        mac.update(&length.to_le_bytes());
+        mac.update(&period.to_le_bytes());
+        WildmonRng::from_seed(mac.finalize().into_bytes().into())
+    }
+
+    /// The Pokémon `identity` has at the time `now`
+    pub fn wildmon_at<'a>(
+        &self,
+        identity: &str,
+        now: SystemTime,
+        pokedex: &'a [Species],
+        opts: &WildmonSettings,
+    ) -> Wildmon<'a> {
+        wildmon(&mut self.rng_at(identity, now), pokedex, opts)
+    }
+
+    /// The Pokémon `identity` has right now
+    pub fn wildmon<'a>(
+        &self,
+        identity: &str,
+        pokedex: &'a [Species],
+        opts: &WildmonSettings,
+    ) -> Wildmon<'a> {
+        self.wildmon_at(identity, SystemTime::now(), pokedex, opts)
+    }
+}
+
+/// Leaves out the secret, so it can't end up in logs
+impl fmt::Debug for StickyNicks {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("StickyNicks")
+            .field("rotation", &self.rotation)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::POKEDEX;
+    use rand::RngCore;
+
+    fn at(secs: u64) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn same_identity_same_nick() {
+        let opts = WildmonSettings::default();
+        let sticky = StickyNicks::new("hunter2");
+        let nick = |sticky: &StickyNicks, identity, secs| {
+            sticky
+                .wildmon_at(identity, at(secs), &POKEDEX, &opts)
+                .to_string()
+        };
+
+        assert_eq!(
+            sticky.rng_at("ash@pallet.town", at(0)).next_u64(),
+            sticky.rng_at("ash@pallet.town", at(1 << 40)).next_u64()
+        );
+        assert_eq!(nick(&sticky, "ash", 0), nick(&sticky, "ash", 1 << 40));
+        let others: Vec<String> = ["gary", "misty", "brock"]
+            .iter()
+            .map(|identity| nick(&sticky, identity, 0))
+            .collect();
+        assert!(others.iter().any(|other| *other != nick(&sticky, "ash", 0)));
+        assert_ne!(
+            StickyNicks::new("hunter3").rng_at("ash", at(0)).next_u64(),
+            sticky.rng_at("ash", at(0)).next_u64()
+        );
+        assert!(!format!("{:?}", sticky).contains("hunter2"));
+
+        // Changing how seeds are derived would rename everybody
+        assert_eq!(sticky.rng_at("ash", at(0)).next_u64(), 9315045284755970296);
+    }
+
+    #[test]
+    fn rotation_periods() {
+        let daily = StickyNicks::new("hunter2").rotation(Rotation::Daily);
+        let rng = |sticky: &StickyNicks, secs| sticky.rng_at("ash", at(secs)).next_u64();
+        assert_eq!(rng(&daily, DAY), rng(&daily, 2 * DAY - 1));
+        assert_ne!(rng(&daily, DAY), rng(&daily, 2 * DAY));
+
+        // Sunday the 4th of January 1970, then Monday the 5th
+        let weekly = StickyNicks::new("hunter2").rotation(Rotation::Weekly);
+        assert_eq!(rng(&weekly, 0), rng(&weekly, 4 * DAY - 1));
+        assert_ne!(rng(&weekly, 4 * DAY - 1), rng(&weekly, 4 * DAY));
+
+        let hourly =
+            StickyNicks::new("hunter2").rotation(Rotation::Every(Duration::from_secs(3600)));
+        assert_eq!(rng(&hourly, 0), rng(&hourly, 3599));
+        assert_ne!(rng(&hourly, 3599), rng(&hourly, 3600));
+        assert_eq!(Rotation::Every(Duration::ZERO).period(at(5)), (5, 1));
+    }
+}
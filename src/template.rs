@@ -0,0 +1,199 @@
+use std::{convert::TryFrom, error::Error, fmt, str::FromStr};
+
+use serde_derive::{Deserialize, Serialize};
+
+/// Widest `{dex:N}` allowed; more than enough for any dex number
+pub const MAX_DEX_WIDTH: usize = 10;
+
+/// The layout nicks have always had
+pub const DEFAULT_TEMPLATE: &str = "{prefix} {name}{gender}{suffix} (lv{level})";
+
+/// How to lay out a nick, like `"{name}{gender} Lv.{level}"`
+///
+/// The placeholders are `{prefix}`, `{name}` (which includes what the Pokémon
+/// was turned into, like "Plushie"), `{gender}` for the symbol, `{level}`,
+/// `{suffix}` and `{dex}`, the national dex number, which takes a zero-padded
+/// width as in `{dex:03}` and is left empty for species without one. `{{` and
+/// `}}` stand for literal braces. After filling it in, runs of spaces are
+/// squashed and the ends trimmed, so an empty prefix or suffix doesn't leave a
+/// gap behind. Unless the settings keep whitespace, every space in the result
+/// then becomes an underscore, the template's own text included; the default
+/// template's spaces are separators like any other.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
+pub struct Template {
+    source: String,
+    pieces: Vec<Piece>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Piece {
+    Text(String),
+    Prefix,
+    Name,
+    Gender,
+    Level,
+    Suffix,
+    /// With the width to pad to
+    Dex(usize),
+}
+
+/// What was wrong with a template
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplateError {
+    /// A `{` without its `}`, or a `}` on its own
+    Unbalanced,
+    UnknownPlaceholder(String),
+    /// A `{dex:N}` wider than `MAX_DEX_WIDTH`
+    DexTooWide(u64),
+}
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemplateError::Unbalanced => write!(f, "unbalanced braces in template"),
+            TemplateError::UnknownPlaceholder(name) => {
+                write!(f, "unknown placeholder `{{{}}}` in template", name)
+            }
+            TemplateError::DexTooWide(width) => write!(
+                f,
+                "`{{dex:{}}}` is wider than the {} digits allowed",
+                width, MAX_DEX_WIDTH
+            ),
+        }
+    }
+}
+
+impl Error for TemplateError {}
+
+impl Template {
+    pub(crate) fn pieces(&self) -> &[Piece] {
+        &self.pieces
+    }
+}
+
+impl Default for Template {
+    fn default() -> Self {
+        DEFAULT_TEMPLATE.parse().expect("Parsing default template")
+    }
+}
+
+impl FromStr for Template {
+    type Err = TemplateError;
+
+    fn from_str(source: &str) -> Result<Template, TemplateError> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '}' => return Err(TemplateError::Unbalanced),
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(TemplateError::Unbalanced),
+                            Some(c) => name.push(c),
+                        }
+                    }
+                    if !text.is_empty() {
+                        pieces.push(Piece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(match name.as_str() {
+                        "prefix" => Piece::Prefix,
+                        "name" => Piece::Name,
+                        "gender" => Piece::Gender,
+                        "level" => Piece::Level,
+                        "suffix" => Piece::Suffix,
+                        "dex" => Piece::Dex(0),
+                        // Parsed wide so it's too wide rather than unknown
+                        // even where usize is 32 bits
+                        _ => match name.strip_prefix("dex:").map(str::parse::<u64>) {
+                            Some(Ok(width)) if width > MAX_DEX_WIDTH as u64 => {
+                                return Err(TemplateError::DexTooWide(width))
+                            }
+                            Some(Ok(width)) => Piece::Dex(width as usize),
+                            _ => return Err(TemplateError::UnknownPlaceholder(name)),
+                        },
+                    });
+                }
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(Piece::Text(text));
+        }
+        Ok(Template {
+            source: source.to_string(),
+            pieces,
+        })
+    }
+}
+
+impl TryFrom<String> for Template {
+    type Error = TemplateError;
+
+    fn try_from(source: String) -> Result<Template, TemplateError> {
+        source.parse()
+    }
+}
+
+impl From<Template> for String {
+    fn from(template: Template) -> String {
+        template.source
+    }
+}
+
+impl fmt::Display for Template {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template_parsing() {
+        let template: Template = "#{dex:03} {name} {{{level}}}".parse().unwrap();
+        assert_eq!(
+            template.pieces(),
+            [
+                Piece::Text("#".into()),
+                Piece::Dex(3),
+                Piece::Text(" ".into()),
+                Piece::Name,
+                Piece::Text(" {".into()),
+                Piece::Level,
+                Piece::Text("}".into()),
+            ]
+        );
+        assert_eq!(template.to_string(), "#{dex:03} {name} {{{level}}}");
+
+        assert_eq!("{name".parse::<Template>(), Err(TemplateError::Unbalanced));
+        assert_eq!("name}".parse::<Template>(), Err(TemplateError::Unbalanced));
+        assert_eq!(
+            "{nmae}".parse::<Template>(),
+            Err(TemplateError::UnknownPlaceholder("nmae".into()))
+        );
+        assert_eq!(
+            "{dex:99999999999}".parse::<Template>(),
+            Err(TemplateError::DexTooWide(99999999999))
+        );
+        assert!("{dex:10}".parse::<Template>().is_ok());
+        assert_eq!(
+            "{dex:x}".parse::<Template>(),
+            Err(TemplateError::UnknownPlaceholder("dex:x".into()))
+        );
+    }
+}
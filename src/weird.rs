@@ -0,0 +1,137 @@
+use rand::Rng;
+use serde_derive::Serialize;
+
+/// Number of faces on the legacy "weird" die; most faces do nothing.
+pub const WEIRD_DIE: u32 = 50;
+
+/// Special prefixes and suffixes from the legacy "weird" roll
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize)]
+pub enum Weird {
+    /// Colosseum/XD
+    Shadow,
+    Imaginary,
+    /// Because if there's Imaginary...
+    Complex,
+    /// Delta Species, applies to the name itself
+    Delta,
+    /// TCG
+    Dark,
+    /// TCG
+    Light,
+    /// A common enough anime motif
+    Giant,
+    /// Orange Islands
+    Pink,
+    /// Not evil, E-D-G-Y!
+    Feral,
+    /// Pokémon-ex
+    Ex,
+    /// Pokémon Prime
+    Prime,
+    /// Pokémon ☆ (TCG)
+    Star,
+    /// Not Feral at all!
+    Civilized,
+    /// Young, so the level gets squashed down to 1-10
+    Baby,
+    /// hax
+    Omnipotent,
+    Plushie,
+    /// Rumble series
+    Toy,
+}
+
+impl Weird {
+    /// Roll the weird die, keeping the legacy 1-in-50 odds for each face.
+    pub fn roll<R: Rng + ?Sized>(rng: &mut R) -> Option<Weird> {
+        Weird::from_roll(rng.gen_range(1..=WEIRD_DIE))
+    }
+
+    /// Map a face of the weird die (1..=50) to its modifier, if any.
+    ///
+    /// 15 and 16 were reserved for Pokémon-EX and LV.X, which were never
+    /// implemented.
+    pub fn from_roll(roll: u32) -> Option<Weird> {
+        Some(match roll {
+            1 => Weird::Shadow,
+            2 => Weird::Imaginary,
+            3 => Weird::Complex,
+            4 => Weird::Delta,
+            5 => Weird::Dark,
+            6 => Weird::Light,
+            7 => Weird::Giant,
+            8 => Weird::Pink,
+            9 => Weird::Feral,
+            11 => Weird::Ex,
+            12 => Weird::Prime,
+            13 | 14 => Weird::Star,
+            17 => Weird::Civilized,
+            18 => Weird::Baby,
+            19 => Weird::Omnipotent,
+            20 => Weird::Plushie,
+            21 => Weird::Toy,
+            _ => return None,
+        })
+    }
+
+    /// Text replacing the usual "Wild" prefix
+    pub fn prefix(&self) -> Option<&'static str> {
+        match self {
+            Weird::Shadow => Some("Shadow"),
+            Weird::Imaginary => Some("Imaginary"),
+            Weird::Complex => Some("Complex"),
+            Weird::Dark => Some("Dark"),
+            Weird::Light => Some("Light"),
+            Weird::Giant => Some("Giant"),
+            Weird::Pink => Some("Pink"),
+            Weird::Feral => Some("Feral"),
+            Weird::Civilized => Some("Civilized"),
+            Weird::Baby => Some("Baby"),
+            Weird::Omnipotent => Some("Omnipotent?"),
+            _ => None,
+        }
+    }
+
+    /// What the Pokémon is turned into instead of getting a prefix, as in
+    /// "Bulbasaur Plushie"
+    pub fn noun(&self) -> Option<&'static str> {
+        match self {
+            Weird::Plushie => Some("Plushie"),
+            Weird::Toy => Some("Toy"),
+            _ => None,
+        }
+    }
+
+    /// Text appended directly to the species name, before the gender symbol
+    pub fn name_suffix(&self) -> Option<&'static str> {
+        match self {
+            Weird::Delta => Some("δ"),
+            _ => None,
+        }
+    }
+
+    /// Text appended after the gender symbol
+    pub fn suffix(&self) -> Option<&'static str> {
+        match self {
+            Weird::Ex => Some("-ex"),
+            Weird::Prime => Some(" Prime"),
+            Weird::Star => Some("☆"),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weird_die_odds() {
+        let faces: Vec<Option<Weird>> = (1..=WEIRD_DIE).map(Weird::from_roll).collect();
+
+        assert_eq!(faces.iter().filter(|w| w.is_some()).count(), 18);
+        assert_eq!(faces.iter().filter(|w| **w == Some(Weird::Star)).count(), 2);
+        assert_eq!(faces.iter().filter(|w| **w == Some(Weird::Toy)).count(), 1);
+        assert_eq!(Weird::from_roll(10), None);
+    }
+}